@@ -0,0 +1,347 @@
+use crate::position::Shape;
+use std::fmt;
+
+// Invariants that prove a finish cannot be reached, whatever jumps are played.
+//
+// Position classes follow Conway's rule of three. Take a linear form f(x, y) = a * x + b * y that
+// is non-zero modulo 3 along every jump direction. The three holes of any jump then fall in three
+// different residues of f, so every jump flips the parity of the number of pegs in each residue.
+// Those parities are fixed up to flipping all three at once, and positions whose parities differ
+// can never reach each other. Square boards have two such forms, triangular boards have one and
+// square boards with diagonal jumps have none.
+//
+// A pagoda function weighs every hole so that, for every jump, the two holes emptied weigh at
+// least as much as the hole landed on. The total weight of the pegs can then never increase, and
+// a position that weighs less than the target can never reach it.
+//
+// Positions are given as the bits of a `Position`.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Class {
+    bits: u8,
+    len: u8,
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for i in (0..self.len).rev() {
+            write!(f, "{}", (self.bits >> i) & 1)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Pagoda {
+    pub name: &'static str,
+    weights: Vec<i64>,
+    // Holes grouped by weight, so that the value is a few population counts
+    levels: Vec<(i64, u128)>,
+}
+
+impl Pagoda {
+    fn new(name: &'static str, weights: Vec<i64>) -> Self {
+        let mut levels: Vec<(i64, u128)> = Vec::new();
+        for (i, weight) in weights.iter().enumerate() {
+            match levels.iter_mut().find(|(w, _)| w == weight) {
+                Some((_, mask)) => *mask |= 1 << i,
+                None => levels.push((*weight, 1 << i)),
+            }
+        }
+
+        Self {
+            name,
+            weights,
+            levels,
+        }
+    }
+
+    pub fn value(&self, pegs: u128) -> i64 {
+        self.levels
+            .iter()
+            .map(|(weight, mask)| weight * (pegs & mask).count_ones() as i64)
+            .sum()
+    }
+}
+
+// Why a finish cannot be reached from a position
+#[derive(Debug, Clone, PartialEq)]
+pub enum Impossibility {
+    Pagoda {
+        name: &'static str,
+        value: i64,
+        target: i64,
+    },
+    PositionClass {
+        class: Class,
+        target: Class,
+    },
+    // No single peg has the class of the position
+    UnmatchedClass(Class),
+    // Every slot is ruled out for the last peg, though not all for the same reason
+    NoFinish,
+}
+
+impl fmt::Display for Impossibility {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Impossibility::Pagoda {
+                name,
+                value,
+                target,
+            } => write!(f, "pagoda value {} < target {} ({})", value, target, name),
+            Impossibility::PositionClass { class, target } => write!(
+                f,
+                "position class {} differs from target class {}",
+                class, target
+            ),
+            Impossibility::UnmatchedClass(class) => {
+                write!(f, "position class {} matches no single slot", class)
+            }
+            Impossibility::NoFinish => write!(f, "no slot can hold the last peg"),
+        }
+    }
+}
+
+pub struct Analysis {
+    // Holes in each residue of every linear form
+    residues: Vec<[u128; 3]>,
+    pagodas: Vec<Pagoda>,
+    // Class of a single peg in each hole
+    hole_classes: Vec<Class>,
+    // Holes of each class that a single peg can end on
+    finishes: Vec<(Class, Vec<usize>)>,
+}
+
+impl Analysis {
+    pub fn new(shape: &Shape) -> Self {
+        let directions = shape.rules().directions(shape.topology());
+        let holes = shape.holes();
+
+        let mut residues = Vec::new();
+        for (a, b) in [(1, 0), (0, 1), (1, 1), (1, 2)].iter() {
+            let form = |x: isize, y: isize| (a * x + b * y).rem_euclid(3) as usize;
+            if directions.iter().any(|(dx, dy)| form(*dx, *dy) == 0) {
+                continue;
+            }
+
+            let mut masks = [0u128; 3];
+            for (i, (x, y)) in holes.iter().enumerate() {
+                masks[form(*x as isize, *y as isize)] |= 1 << i;
+            }
+            residues.push(masks);
+        }
+
+        let pagodas = candidate_pagodas(holes)
+            .into_iter()
+            .filter(|p| {
+                shape
+                    .jumps()
+                    .iter()
+                    .all(|j| p.weights[j.from] + p.weights[j.over] >= p.weights[j.to])
+            })
+            .collect();
+
+        let mut analysis = Self {
+            residues,
+            pagodas,
+            hole_classes: Vec::new(),
+            finishes: Vec::new(),
+        };
+        analysis.hole_classes = (0..holes.len()).map(|i| analysis.class(1 << i)).collect();
+        for (hole, class) in analysis.hole_classes.iter().enumerate() {
+            match analysis.finishes.iter_mut().find(|(c, _)| c == class) {
+                Some((_, holes)) => holes.push(hole),
+                None => analysis.finishes.push((*class, vec![hole])),
+            }
+        }
+        analysis
+    }
+
+    pub fn class(&self, pegs: u128) -> Class {
+        let mut bits = 0u8;
+        for masks in &self.residues {
+            let parity = |r: usize| ((pegs & masks[r]).count_ones() & 1) as u8;
+            // flipping all three parities gives the same class
+            bits = bits << 2 | (parity(1) ^ parity(0)) << 1 | (parity(2) ^ parity(0));
+        }
+
+        Class {
+            bits,
+            len: 2 * self.residues.len() as u8,
+        }
+    }
+
+    // Whether `explain` would find a reason, but cheaper
+    pub fn is_lost(&self, pegs: u128, target: Option<usize>) -> bool {
+        let class = self.class(pegs);
+        match target {
+            Some(t) => {
+                class != self.hole_classes[t]
+                    || self.pagodas.iter().any(|p| p.value(pegs) < p.weights[t])
+            }
+            None => {
+                let finishes = match self.finishes.iter().find(|(c, _)| *c == class) {
+                    Some((_, holes)) => holes,
+                    None => return true,
+                };
+
+                let values: Vec<i64> = self.pagodas.iter().map(|p| p.value(pegs)).collect();
+                !finishes.iter().any(|t| {
+                    self.pagodas
+                        .iter()
+                        .zip(&values)
+                        .all(|(p, value)| *value >= p.weights[*t])
+                })
+            }
+        }
+    }
+
+    // Explains why the pegs can never be reduced to a single peg in `target`, or in any hole if
+    // there is no target. Returns `None` if no invariant rules the finish out, which does not mean
+    // that it can be reached.
+    pub fn explain(&self, pegs: u128, target: Option<usize>) -> Option<Impossibility> {
+        let class = self.class(pegs);
+        let values: Vec<i64> = self.pagodas.iter().map(|p| p.value(pegs)).collect();
+
+        match target {
+            Some(t) => self.rule_out(class, &values, t),
+            None => {
+                let mut holes = 0..self.hole_classes.len();
+                if holes.any(|t| self.rule_out(class, &values, t).is_none()) {
+                    None
+                } else if self.hole_classes.iter().all(|c| *c != class) {
+                    Some(Impossibility::UnmatchedClass(class))
+                } else {
+                    Some(Impossibility::NoFinish)
+                }
+            }
+        }
+    }
+
+    fn rule_out(&self, class: Class, values: &[i64], target: usize) -> Option<Impossibility> {
+        let target_class = self.hole_classes[target];
+        if class != target_class {
+            return Some(Impossibility::PositionClass {
+                class,
+                target: target_class,
+            });
+        }
+
+        self.pagodas
+            .iter()
+            .zip(values)
+            .find(|(p, value)| **value < p.weights[target])
+            .map(|(p, value)| Impossibility::Pagoda {
+                name: p.name,
+                value: *value,
+                target: p.weights[target],
+            })
+    }
+}
+
+// Longest side for which the Fibonacci weights are used, so that they do not overflow
+const MAX_FIBONACCI_SPAN: usize = 60;
+
+// Pagoda functions to try on any board. Those that do not hold for the jumps of the board are
+// discarded by the caller.
+fn candidate_pagodas(holes: &[(usize, usize)]) -> Vec<Pagoda> {
+    let min_x = holes.iter().map(|(x, _)| *x).min().unwrap_or(0);
+    let max_x = holes.iter().map(|(x, _)| *x).max().unwrap_or(0);
+    let min_y = holes.iter().map(|(_, y)| *y).min().unwrap_or(0);
+    let max_y = holes.iter().map(|(_, y)| *y).max().unwrap_or(0);
+
+    let pagoda = |name, weight: &dyn Fn(usize, usize) -> i64| {
+        Pagoda::new(name, holes.iter().map(|(x, y)| weight(*x, *y)).collect())
+    };
+
+    // the parity of a coordinate alternates along every jump, so the emptied holes always
+    // include one of each parity
+    let mut pagodas = vec![
+        pagoda("even columns", &|x, _| (x % 2 == 0) as i64),
+        pagoda("odd columns", &|x, _| (x % 2 == 1) as i64),
+        pagoda("even rows", &|_, y| (y % 2 == 0) as i64),
+        pagoda("odd rows", &|_, y| (y % 2 == 1) as i64),
+        pagoda("even columns of even rows", &|x, y| {
+            (x % 2 == 0 && y % 2 == 0) as i64
+        }),
+        pagoda("odd columns of even rows", &|x, y| {
+            (x % 2 == 1 && y % 2 == 0) as i64
+        }),
+        pagoda("even columns of odd rows", &|x, y| {
+            (x % 2 == 0 && y % 2 == 1) as i64
+        }),
+        pagoda("odd columns of odd rows", &|x, y| {
+            (x % 2 == 1 && y % 2 == 1) as i64
+        }),
+    ];
+
+    // weights that grow towards one side of the board like the Fibonacci numbers, which pegs
+    // can only reach by sacrificing many others
+    if max_x - min_x < MAX_FIBONACCI_SPAN && max_y - min_y < MAX_FIBONACCI_SPAN {
+        pagodas.push(pagoda("Fibonacci towards the right", &|x, _| {
+            fibonacci(x - min_x)
+        }));
+        pagodas.push(pagoda("Fibonacci towards the left", &|x, _| {
+            fibonacci(max_x - x)
+        }));
+        pagodas.push(pagoda("Fibonacci towards the bottom", &|_, y| {
+            fibonacci(y - min_y)
+        }));
+        pagodas.push(pagoda("Fibonacci towards the top", &|_, y| {
+            fibonacci(max_y - y)
+        }));
+    }
+
+    pagodas
+}
+
+// 1, 1, 2, 3, 5, ...
+fn fibonacci(n: usize) -> i64 {
+    let (mut a, mut b) = (1, 1);
+    for _ in 0..n {
+        let next = a + b;
+        a = b;
+        b = next;
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::testing::variant;
+
+    // Game on the variant, started with the given slot left empty
+    fn start(key: &str, empty: (usize, usize)) -> Game {
+        let level = variant(key).with_start(empty.0, empty.1).unwrap();
+        Game::new(&level).unwrap()
+    }
+
+    #[test]
+    fn proves_the_french_board_lost_from_the_centre() {
+        let game = start("french", (3, 3));
+        let (analysis, pegs) = (Analysis::new(game.shape()), game.position().bits());
+        let reason = analysis.explain(pegs, None);
+        assert!(
+            matches!(reason, Some(Impossibility::UnmatchedClass(_))),
+            "{:?}",
+            reason
+        );
+        assert!(analysis.is_lost(pegs, None));
+    }
+
+    #[test]
+    fn leaves_solvable_starts_alone() {
+        let game = start("french", (3, 1));
+        let (analysis, pegs) = (Analysis::new(game.shape()), game.position().bits());
+        assert_eq!(analysis.explain(pegs, None), None);
+        assert!(!analysis.is_lost(pegs, None));
+
+        let game = start("english", (3, 3));
+        let (analysis, pegs) = (Analysis::new(game.shape()), game.position().bits());
+        let centre = game.shape().hole_at(3, 3);
+        assert_eq!(analysis.explain(pegs, centre), None);
+        assert!(!analysis.is_lost(pegs, centre));
+    }
+}
@@ -0,0 +1,83 @@
+use crate::game::Game;
+use crate::solver::{Interrupted, SlotJump, Solution, Solver, SEARCH_BUDGET};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+// Searches run on another thread, so that front-ends stay responsive while the solver works. A
+// front-end keeps the search for the position it is about and replaces it when the position
+// changes; dropping a search cancels it.
+pub struct BackgroundSearch<T> {
+    cancel: Arc<AtomicBool>,
+    result: Arc<Mutex<Option<T>>>,
+}
+
+impl<T: Send + 'static> BackgroundSearch<T> {
+    // Runs the search on a new thread. The search should give up soon after the flag it is given
+    // is set, and returns `None` if it has nothing to report.
+    pub fn spawn<F>(search: F) -> Self
+    where
+        F: FnOnce(&AtomicBool) -> Option<T> + Send + 'static,
+    {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let result = Arc::new(Mutex::new(None));
+
+        let background = Self {
+            cancel: cancel.clone(),
+            result: result.clone(),
+        };
+
+        thread::spawn(move || {
+            if let Some(found) = search(&cancel) {
+                *result.lock().expect("search result poisoned") = Some(found);
+            }
+        });
+
+        background
+    }
+
+    // Result of the search, once it has finished
+    pub fn take_result(&self) -> Option<T> {
+        self.result.lock().expect("search result poisoned").take()
+    }
+}
+
+impl<T> Drop for BackgroundSearch<T> {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hint {
+    // First jump of a solution
+    Jump(SlotJump),
+    // No solution from the position
+    Unsolvable,
+    // The search ran out of budget before finding either
+    GaveUp,
+}
+
+impl Hint {
+    // Hint from a search for the goal of the game, or `None` if the search was cancelled
+    pub fn from_search(search: Result<Option<Solution>, Interrupted>) -> Option<Self> {
+        match search {
+            Ok(solution) => Some(
+                solution
+                    .and_then(|s| s.jumps.first().copied())
+                    .map_or(Hint::Unsolvable, Hint::Jump),
+            ),
+            Err(Interrupted::OutOfBudget) => Some(Hint::GaveUp),
+            Err(Interrupted::Cancelled) => None,
+        }
+    }
+}
+
+// Looks for the first jump of a solution from the current position of the game
+pub fn hint(game: &Game) -> BackgroundSearch<Hint> {
+    let solver = Solver::from_game(game);
+    BackgroundSearch::spawn(move |cancel| match solver {
+        Some(solver) => Hint::from_search(solver.solve_cancellable(cancel, SEARCH_BUDGET)),
+        None => Some(Hint::Unsolvable),
+    })
+}
@@ -0,0 +1,178 @@
+use crate::position::MAX_HOLES;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// Parts shared by the level and game record files. Both start with a header of `key: value` lines,
+// ended by a blank line, and report errors with the line and column they were found at.
+
+// Header value, with the line and column it starts at
+pub type Field = (usize, usize, String);
+
+// Lines of a file numbered from 1, without trailing whitespace
+pub fn lines(s: &str) -> impl Iterator<Item = (usize, &str)> {
+    s.lines().enumerate().map(|(i, l)| (i + 1, l.trim_end()))
+}
+
+// Header values by key
+pub struct Header {
+    fields: HashMap<&'static str, Field>,
+}
+
+impl Header {
+    // Reads header lines up to the first blank line. Every key must be one of `keys` and may appear
+    // only once.
+    pub fn parse<'a>(
+        lines: &mut impl Iterator<Item = (usize, &'a str)>,
+        keys: &[&'static str],
+    ) -> Result<Self, ParseError> {
+        let mut fields = HashMap::new();
+
+        for (line_no, line) in lines {
+            if line.is_empty() {
+                break;
+            }
+
+            let sep = line
+                .find(':')
+                .ok_or_else(|| ParseError::new(line_no, 1, ParseErrorKind::MalformedHeader))?;
+            let key = line[..sep].trim();
+            let value = line[sep + 1..].trim();
+            let value_column = line.len() - line[sep + 1..].trim_start().len() + 1;
+
+            let key = keys.iter().find(|k| **k == key).ok_or_else(|| {
+                ParseError::new(line_no, 1, ParseErrorKind::UnknownKey(key.to_string()))
+            })?;
+
+            if fields.contains_key(key) {
+                return Err(ParseError::new(
+                    line_no,
+                    1,
+                    ParseErrorKind::DuplicateKey(key.to_string()),
+                ));
+            }
+
+            fields.insert(*key, (line_no, value_column, value.to_string()));
+        }
+
+        Ok(Self { fields })
+    }
+
+    pub fn take(&mut self, key: &'static str) -> Option<Field> {
+        self.fields.remove(key)
+    }
+
+    // Value of a key every file must have
+    pub fn required(&mut self, key: &'static str) -> Result<String, ParseError> {
+        self.take(key)
+            .map(|(_, _, v)| v)
+            .ok_or_else(|| ParseError::new(1, 1, ParseErrorKind::MissingKey(key)))
+    }
+
+    // Value of a key that may be left out, empty if it is
+    pub fn optional(&mut self, key: &'static str) -> String {
+        self.take(key).map(|(_, _, v)| v).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseErrorKind {
+    MalformedHeader,
+    UnknownKey(String),
+    DuplicateKey(String),
+    MissingKey(&'static str),
+    InvalidGoal(String),
+    InvalidTopology(String),
+    InvalidRules(String),
+    InvalidStart(String),
+    InvalidSlot(char),
+    OutsideBoard,
+    MissingBoard,
+    NoEmptySlot,
+    TooManySlots(usize),
+    InvalidMove(String),
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseErrorKind::MalformedHeader => write!(f, "expected a `key: value` header line"),
+            ParseErrorKind::UnknownKey(k) => write!(f, "unknown header key `{}`", k),
+            ParseErrorKind::DuplicateKey(k) => write!(f, "duplicate header key `{}`", k),
+            ParseErrorKind::MissingKey(k) => write!(f, "missing header key `{}`", k),
+            ParseErrorKind::InvalidGoal(g) => write!(f, "invalid goal `{}`", g),
+            ParseErrorKind::InvalidTopology(t) => write!(f, "invalid topology `{}`", t),
+            ParseErrorKind::InvalidRules(r) => write!(f, "invalid rules `{}`", r),
+            ParseErrorKind::InvalidStart(s) => write!(f, "invalid start slot `{}`", s),
+            ParseErrorKind::InvalidSlot(c) => {
+                write!(f, "invalid slot `{}` (expected `X`, `O` or `.`)", c)
+            }
+            ParseErrorKind::OutsideBoard => write!(f, "slot lies outside the board"),
+            ParseErrorKind::MissingBoard => write!(f, "level has no board"),
+            ParseErrorKind::NoEmptySlot => write!(f, "board has no empty slot"),
+            ParseErrorKind::TooManySlots(n) => {
+                write!(f, "board has {} slots (at most {} allowed)", n, MAX_HOLES)
+            }
+            ParseErrorKind::InvalidMove(m) => {
+                write!(f, "invalid move `{}` (expected e.g. `d2-d4`)", m)
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, kind: ParseErrorKind) -> Self {
+        Self { line, column, kind }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.kind
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug)]
+pub enum FileError {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, ParseError),
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FileError::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+            FileError::Parse(path, err) => write!(f, "{}: {}", path.display(), err),
+        }
+    }
+}
+
+impl std::error::Error for FileError {}
+
+pub fn load<T, P>(path: P) -> Result<T, FileError>
+where
+    T: FromStr<Err = ParseError>,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path).map_err(|e| FileError::Io(path.to_owned(), e))?;
+    contents
+        .parse()
+        .map_err(|e| FileError::Parse(path.to_owned(), e))
+}
@@ -0,0 +1,261 @@
+use crate::history::{HistoryEntry, MoveHistory};
+use crate::level::{Goal, Level};
+use crate::position::{Jump, Position, Shape};
+use crate::rules::Rules;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameStatus {
+    OnGoing,
+    Completed,
+    Solved,
+}
+
+// A game in progress: the position on the board, the jumps played so far and the score
+pub struct Game {
+    shape: Shape,
+    position: Position,
+    goal: Goal,
+    status: GameStatus,
+    move_count: u16,
+    jump_count: u16,
+    // Hole the last jump landed on, used to group chained jumps into a single move
+    last_landing: Option<usize>,
+    history: MoveHistory,
+}
+
+impl Game {
+    // Starts a game on the level. Returns `None` if the board has more than `MAX_HOLES` slots.
+    pub fn new(level: &Level) -> Option<Self> {
+        let shape = Shape::from_level(level)?;
+        let position = Position::from_level(&shape, level);
+
+        let mut game = Self {
+            shape,
+            position,
+            goal: level.goal,
+            status: GameStatus::OnGoing,
+            move_count: 0,
+            jump_count: 0,
+            last_landing: None,
+            history: MoveHistory::default(),
+        };
+        game.update_status();
+        Some(game)
+    }
+
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    pub fn goal(&self) -> Goal {
+        self.goal
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.shape.rules()
+    }
+
+    pub fn status(&self) -> GameStatus {
+        self.status
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.status != GameStatus::OnGoing
+    }
+
+    pub fn move_count(&self) -> u16 {
+        self.move_count
+    }
+
+    pub fn jump_count(&self) -> u16 {
+        self.jump_count
+    }
+
+    pub fn peg_count(&self) -> u16 {
+        self.position.count()
+    }
+
+    // First slot without a peg, where a cursor starts
+    pub fn first_empty_slot(&self) -> (usize, usize) {
+        (0..self.shape.holes().len())
+            .find(|hole| !self.position.is_occupied(*hole))
+            .map(|hole| self.shape.hole(hole))
+            .unwrap_or_else(|| self.shape.hole(0))
+    }
+
+    pub fn history(&self) -> &MoveHistory {
+        &self.history
+    }
+
+    // Jumps that can be played from the current position. These are the only ones `play` accepts.
+    pub fn legal_jumps(&self) -> impl Iterator<Item = Jump> + '_ {
+        self.position.legal_jumps(&self.shape)
+    }
+
+    // Slots the peg on the given slot can jump to
+    pub fn destinations(&self, from: (usize, usize)) -> Vec<(usize, usize)> {
+        let from = self.shape.hole_at(from.0, from.1);
+        self.legal_jumps()
+            .filter(|j| Some(j.from) == from)
+            .map(|j| self.shape.hole(j.to))
+            .collect()
+    }
+
+    // Slots holding a peg that can jump
+    pub fn movable_pegs(&self) -> Vec<(usize, usize)> {
+        let mut pegs: Vec<(usize, usize)> = self
+            .legal_jumps()
+            .map(|j| self.shape.hole(j.from))
+            .collect();
+        pegs.sort_unstable();
+        pegs.dedup();
+        pegs
+    }
+
+    // Plays the jump from one slot to another. Returns `None`, leaving the game unchanged, if the
+    // jump is not legal.
+    pub fn play(&mut self, from: (usize, usize), to: (usize, usize)) -> Option<Jump> {
+        let from = self.shape.hole_at(from.0, from.1)?;
+        let to = self.shape.hole_at(to.0, to.1)?;
+        let jump = self.legal_jumps().find(|j| j.from == from && j.to == to)?;
+
+        let entry = HistoryEntry {
+            jump,
+            chained: self.rules().chain_jumps && self.last_landing == Some(jump.from),
+        };
+        self.apply(&entry);
+        self.history.push(entry);
+        Some(jump)
+    }
+
+    // Takes back the last jump
+    pub fn undo(&mut self) -> Option<Jump> {
+        let entry = self.history.undo()?;
+        self.position.undo(&entry.jump);
+
+        if !entry.chained {
+            self.move_count -= 1;
+        }
+        self.jump_count -= 1;
+        self.last_landing = self.history.last().map(|e| e.jump.to);
+        // there was a move available before any jump, so the game is back on
+        self.status = GameStatus::OnGoing;
+        Some(entry.jump)
+    }
+
+    // Plays the last jump taken back again
+    pub fn redo(&mut self) -> Option<Jump> {
+        let entry = self.history.redo()?;
+        self.apply(&entry);
+        Some(entry.jump)
+    }
+
+    // Takes back every jump, leaving them to be redone one at a time
+    pub fn rewind(&mut self) {
+        while self.undo().is_some() {}
+    }
+
+    fn apply(&mut self, entry: &HistoryEntry) {
+        self.position.apply(&entry.jump);
+
+        if !entry.chained {
+            self.move_count += 1;
+        }
+        self.jump_count += 1;
+        self.last_landing = Some(entry.jump.to);
+        self.update_status();
+    }
+
+    // Ends the game once no jump is left
+    fn update_status(&mut self) {
+        if self.legal_jumps().next().is_some() {
+            return;
+        }
+
+        let remaining: Vec<(usize, usize)> = self
+            .position
+            .pegs()
+            .map(|hole| self.shape.hole(hole))
+            .collect();
+        self.status = if self.goal.is_met(&remaining) {
+            GameStatus::Solved
+        } else {
+            GameStatus::Completed
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{game, variant};
+
+    #[test]
+    fn illegal_jumps_leave_the_game_unchanged() {
+        let mut game = Game::new(&variant("english")).unwrap();
+        assert_eq!(game.play((3, 0), (3, 2)), None);
+        assert_eq!(game.play((3, 3), (3, 1)), None);
+        // slots far outside the board, as a record may name them
+        assert_eq!(game.play((3, usize::MAX), (3, 3)), None);
+        assert_eq!(game.play((usize::MAX, 3), (3, 3)), None);
+        assert_eq!(game.move_count(), 0);
+        assert_eq!(game.peg_count(), 32);
+    }
+
+    #[test]
+    fn chained_jumps_count_as_one_move() {
+        let mut game = game("name: Line\nrules: chains\n\nXXOXO\n");
+        assert!(game.play((0, 0), (2, 0)).is_some());
+        assert!(game.play((2, 0), (4, 0)).is_some());
+        assert_eq!(game.status(), GameStatus::Solved);
+        assert_eq!((game.move_count(), game.jump_count()), (1, 2));
+    }
+
+    #[test]
+    fn undo_and_redo_restore_counts_and_status() {
+        let mut game = game("name: Line\n\nXXO.X\n");
+        assert!(game.play((0, 0), (2, 0)).is_some());
+        assert_eq!(game.status(), GameStatus::Completed);
+        assert_eq!(
+            (game.move_count(), game.jump_count(), game.peg_count()),
+            (1, 1, 2)
+        );
+
+        assert!(game.undo().is_some());
+        assert_eq!(game.status(), GameStatus::OnGoing);
+        assert_eq!(
+            (game.move_count(), game.jump_count(), game.peg_count()),
+            (0, 0, 3)
+        );
+        assert!(game.undo().is_none());
+
+        assert!(game.redo().is_some());
+        assert_eq!(game.status(), GameStatus::Completed);
+        assert_eq!(
+            (game.move_count(), game.jump_count(), game.peg_count()),
+            (1, 1, 2)
+        );
+        assert!(game.redo().is_none());
+    }
+
+    #[test]
+    fn undo_and_redo_keep_chained_moves_together() {
+        let mut game = game("name: Line\nrules: chains\n\nXXOXO\n");
+        assert!(game.play((0, 0), (2, 0)).is_some());
+        assert!(game.play((2, 0), (4, 0)).is_some());
+
+        game.rewind();
+        assert_eq!(game.status(), GameStatus::OnGoing);
+        assert_eq!((game.move_count(), game.jump_count()), (0, 0));
+
+        assert!(game.redo().is_some());
+        assert_eq!((game.move_count(), game.jump_count()), (1, 1));
+        assert!(game.redo().is_some());
+        assert_eq!(game.status(), GameStatus::Solved);
+        assert_eq!((game.move_count(), game.jump_count()), (1, 2));
+    }
+}
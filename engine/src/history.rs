@@ -0,0 +1,45 @@
+use crate::position::Jump;
+
+// A jump that has been played, along with what is needed to take it back
+#[derive(Debug, Clone, Copy)]
+pub struct HistoryEntry {
+    pub jump: Jump,
+    // The jump continued a chain and did not count as a new move
+    pub chained: bool,
+}
+
+// Jumps played so far, plus the ones that have been undone and can be redone
+#[derive(Default)]
+pub struct MoveHistory {
+    done: Vec<HistoryEntry>,
+    undone: Vec<HistoryEntry>,
+}
+
+impl MoveHistory {
+    // Records a new jump. Playing a new jump discards anything that could have been redone.
+    pub fn push(&mut self, entry: HistoryEntry) {
+        self.done.push(entry);
+        self.undone.clear();
+    }
+
+    pub fn undo(&mut self) -> Option<HistoryEntry> {
+        let entry = self.done.pop()?;
+        self.undone.push(entry);
+        Some(entry)
+    }
+
+    pub fn redo(&mut self) -> Option<HistoryEntry> {
+        let entry = self.undone.pop()?;
+        self.done.push(entry);
+        Some(entry)
+    }
+
+    // Jumps played so far, oldest first
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.done
+    }
+
+    pub fn last(&self) -> Option<&HistoryEntry> {
+        self.done.last()
+    }
+}
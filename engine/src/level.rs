@@ -0,0 +1,364 @@
+use crate::file::{self, FileError, Header};
+use crate::game::Game;
+use crate::notation;
+use crate::position::MAX_HOLES;
+use crate::rules::Rules;
+use crate::topology::Topology;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+pub use crate::file::{ParseError, ParseErrorKind};
+
+// Level files consist of a header of `key: value` lines, followed by a blank line and the board.
+// The board uses the same alphabet as the original hard-coded layout:
+//   X - slot with a peg
+//   O - empty slot
+//   . - no slot
+//
+// Triangular boards set `topology: triangular` and write every row left-aligned.
+// Optional rules are listed in the `rules` header, e.g. `rules: diagonal`.
+//
+// Example:
+//   name: English
+//   author: Traditional
+//   goal: centre
+//
+//   ..XXX..
+//   ..XXX..
+//   XXXXXXX
+//   XXXOXXX
+//   XXXXXXX
+//   ..XXX..
+//   ..XXX..
+
+pub const LEVEL_EXTENSION: &str = "lvl";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Goal {
+    SinglePeg,
+    SinglePegAt { x: usize, y: usize },
+}
+
+impl Goal {
+    pub fn is_met(&self, remaining: &[(usize, usize)]) -> bool {
+        match (self, remaining) {
+            (Goal::SinglePeg, [_]) => true,
+            (Goal::SinglePegAt { x, y }, [(px, py)]) => x == px && y == py,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Level {
+    pub name: String,
+    pub author: String,
+    pub goal: Goal,
+    pub topology: Topology,
+    pub rules: Rules,
+    pub layout: Vec<Vec<char>>,
+}
+
+impl Level {
+    pub fn title(&self) -> String {
+        if self.author.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} by {}", self.name, self.author)
+        }
+    }
+
+    // Slots of the board
+    pub fn holes(&self) -> Vec<(usize, usize)> {
+        self.slots(|c| c == 'X' || c == 'O')
+    }
+
+    // Slots holding a peg at the start
+    pub fn pegs(&self) -> Vec<(usize, usize)> {
+        self.slots(|c| c == 'X')
+    }
+
+    // The same board with pegs in every slot but the given one. Returns `None` if there is no slot
+    // there.
+    pub fn with_start(&self, x: usize, y: usize) -> Option<Level> {
+        if !self.holes().contains(&(x, y)) {
+            return None;
+        }
+
+        let mut level = self.clone();
+        for c in level.layout.iter_mut().flat_map(|row| row.iter_mut()) {
+            if *c == 'O' {
+                *c = 'X';
+            }
+        }
+        level.layout[y][x] = 'O';
+        Some(level)
+    }
+
+    // Starts a game on the board, leaving the given slot empty instead of the usual ones if there
+    // is a slot there
+    pub fn start_game(&self, start: Option<(usize, usize)>) -> Game {
+        let level = start.and_then(|(x, y)| self.with_start(x, y));
+        Game::new(level.as_ref().unwrap_or(self)).expect("level has too many slots")
+    }
+
+    fn slots(&self, filter: impl Fn(char) -> bool) -> Vec<(usize, usize)> {
+        let mut slots = Vec::new();
+        for (y, row) in self.layout.iter().enumerate() {
+            for (x, c) in row.iter().enumerate() {
+                if filter(*c) {
+                    slots.push((x, y));
+                }
+            }
+        }
+        slots
+    }
+}
+
+impl FromStr for Level {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = file::lines(s);
+
+        // header
+        let mut header =
+            Header::parse(&mut lines, &["name", "author", "goal", "topology", "rules"])?;
+        let name = header.required("name")?;
+        let author = header.optional("author");
+        let goal = header.take("goal");
+        let topology = header.take("topology");
+        let rules = header.take("rules");
+
+        let topology = match topology {
+            None => Topology::default(),
+            Some((line_no, col, value)) => value.parse().map_err(|_| {
+                ParseError::new(line_no, col, ParseErrorKind::InvalidTopology(value))
+            })?,
+        };
+        let rules = match rules {
+            None => Rules::default(),
+            Some((line_no, col, value)) => value
+                .parse()
+                .map_err(|_| ParseError::new(line_no, col, ParseErrorKind::InvalidRules(value)))?,
+        };
+
+        // board
+        let mut layout = Vec::new();
+        let mut board_start = None;
+        for (line_no, line) in lines {
+            if line.is_empty() && layout.is_empty() {
+                continue;
+            }
+
+            board_start.get_or_insert(line_no);
+
+            let mut row = Vec::new();
+            for (col, c) in line.chars().enumerate() {
+                match c {
+                    '.' => row.push(c),
+                    'X' | 'O' if topology.contains(col, layout.len()) => row.push(c),
+                    'X' | 'O' => {
+                        return Err(ParseError::new(
+                            line_no,
+                            col + 1,
+                            ParseErrorKind::OutsideBoard,
+                        ))
+                    }
+                    _ => {
+                        return Err(ParseError::new(
+                            line_no,
+                            col + 1,
+                            ParseErrorKind::InvalidSlot(c),
+                        ))
+                    }
+                }
+            }
+
+            layout.push(row);
+        }
+
+        // ignore trailing blank lines
+        while layout.last().is_some_and(|row| row.is_empty()) {
+            layout.pop();
+        }
+
+        let board_start = match board_start {
+            Some(l) if !layout.is_empty() => l,
+            _ => {
+                let last_line = s.lines().count().max(1);
+                return Err(ParseError::new(last_line, 1, ParseErrorKind::MissingBoard));
+            }
+        };
+
+        if !layout.iter().flat_map(|row| row.iter()).any(|c| *c == 'O') {
+            return Err(ParseError::new(board_start, 1, ParseErrorKind::NoEmptySlot));
+        }
+
+        let slot_count = layout
+            .iter()
+            .flat_map(|row| row.iter())
+            .filter(|c| **c != '.')
+            .count();
+        if slot_count > MAX_HOLES {
+            return Err(ParseError::new(
+                board_start,
+                1,
+                ParseErrorKind::TooManySlots(slot_count),
+            ));
+        }
+
+        let goal = match goal {
+            None => Goal::SinglePeg,
+            Some((line_no, col, value)) => parse_goal(&value, topology, &layout)
+                .ok_or_else(|| ParseError::new(line_no, col, ParseErrorKind::InvalidGoal(value)))?,
+        };
+
+        Ok(Self {
+            name,
+            author,
+            goal,
+            topology,
+            rules,
+            layout,
+        })
+    }
+}
+
+fn parse_goal(value: &str, topology: Topology, layout: &[Vec<char>]) -> Option<Goal> {
+    match value {
+        "single" => Some(Goal::SinglePeg),
+        "centre" | "center" if topology == Topology::Square => {
+            let height = layout.len();
+            let width = layout.iter().map(|row| row.len()).max().unwrap_or(0);
+            if width.is_multiple_of(2) || height.is_multiple_of(2) {
+                return None;
+            }
+
+            let (x, y) = (width / 2, height / 2);
+            match layout[y].get(x) {
+                Some('X') | Some('O') => Some(Goal::SinglePegAt { x, y }),
+                _ => None,
+            }
+        }
+        _ => {
+            let (x, y) = notation::parse_slot(value)?;
+            match layout.get(y).and_then(|row| row.get(x)) {
+                Some('X') | Some('O') => Some(Goal::SinglePegAt { x, y }),
+                _ => None,
+            }
+        }
+    }
+}
+
+pub type LevelError = FileError;
+
+pub fn load_level<P: AsRef<Path>>(path: P) -> Result<Level, LevelError> {
+    file::load(path)
+}
+
+// Loads all level files in the given directory, sorted by file name. A file that cannot be loaded
+// does not stop the others: the levels that loaded are returned along with an error for each file
+// that did not. Fails only if the directory cannot be read.
+pub fn load_levels<P: AsRef<Path>>(dir: P) -> Result<(Vec<Level>, Vec<LevelError>), LevelError> {
+    let dir = dir.as_ref();
+    let entries = fs::read_dir(dir).map_err(|e| LevelError::Io(dir.to_owned(), e))?;
+
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| LevelError::Io(dir.to_owned(), e))?.path();
+        if path.extension().is_some_and(|ext| ext == LEVEL_EXTENSION) {
+            paths.push(path);
+        }
+    }
+
+    paths.sort();
+    let mut levels = Vec::new();
+    let mut errors = Vec::new();
+    for path in paths {
+        match load_level(path) {
+            Ok(level) => levels.push(level),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    Ok((levels, errors))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::parse_error;
+
+    #[test]
+    fn parses_header_and_board() {
+        let level: Level = "name: Cross\nauthor: Someone\ngoal: b2\n\n.X.\nXOX\n.X.\n"
+            .parse()
+            .unwrap();
+        assert_eq!(level.title(), "Cross by Someone");
+        assert_eq!(level.goal, Goal::SinglePegAt { x: 1, y: 1 });
+        assert_eq!(level.holes().len(), 5);
+        assert_eq!(level.pegs().len(), 4);
+    }
+
+    #[test]
+    fn header_errors_point_at_the_line() {
+        assert_eq!(
+            parse_error::<Level>("name: Test\nsize: 7\n\nXO\n"),
+            ParseError::new(2, 1, ParseErrorKind::UnknownKey("size".to_string()))
+        );
+        assert_eq!(
+            parse_error::<Level>("name: Test\nname: Again\n\nXO\n"),
+            ParseError::new(2, 1, ParseErrorKind::DuplicateKey("name".to_string()))
+        );
+        assert_eq!(
+            parse_error::<Level>("name: Test\nno separator\n\nXO\n"),
+            ParseError::new(2, 1, ParseErrorKind::MalformedHeader)
+        );
+        assert_eq!(
+            parse_error::<Level>("author: Nobody\n\nXO\n"),
+            ParseError::new(1, 1, ParseErrorKind::MissingKey("name"))
+        );
+    }
+
+    #[test]
+    fn value_errors_point_at_the_value() {
+        assert_eq!(
+            parse_error::<Level>("name: Test\ngoal:   zz99\n\nXO\n"),
+            ParseError::new(2, 9, ParseErrorKind::InvalidGoal("zz99".to_string()))
+        );
+        assert_eq!(
+            parse_error::<Level>("name: Test\ntopology: round\n\nXO\n"),
+            ParseError::new(2, 11, ParseErrorKind::InvalidTopology("round".to_string()))
+        );
+    }
+
+    #[test]
+    fn board_errors_point_at_the_slot() {
+        assert_eq!(
+            parse_error::<Level>("name: Test\n\n.X.\nXOY\n"),
+            ParseError::new(4, 3, ParseErrorKind::InvalidSlot('Y'))
+        );
+        assert_eq!(
+            parse_error::<Level>("name: Test\ntopology: triangular\n\nO\nXX\nXXX.X\n"),
+            ParseError::new(6, 5, ParseErrorKind::OutsideBoard)
+        );
+        assert_eq!(
+            parse_error::<Level>("name: Test\n\n\nXX\nXX\n"),
+            ParseError::new(4, 1, ParseErrorKind::NoEmptySlot)
+        );
+        assert_eq!(
+            parse_error::<Level>("name: Test\n\n"),
+            ParseError::new(2, 1, ParseErrorKind::MissingBoard)
+        );
+    }
+
+    #[test]
+    fn start_game_leaves_the_start_slot_empty() {
+        let level: Level = "name: Line\n\nXXOX\n".parse().unwrap();
+        let game = level.start_game(Some((0, 0)));
+        assert_eq!(game.first_empty_slot(), (0, 0));
+        assert_eq!(game.peg_count(), 3);
+    }
+}
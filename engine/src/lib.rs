@@ -0,0 +1,23 @@
+// Rules of peg solitaire, independent of any front-end: boards and their positions, move
+// validation, game-over detection, levels and their lints, game records and the solver, which
+// can run in the background. Also the board catalogue and the board options front-ends set up
+// games with.
+
+pub mod analysis;
+pub mod background;
+pub mod file;
+pub mod game;
+pub mod history;
+pub mod level;
+pub mod lint;
+pub mod notation;
+pub mod options;
+pub mod position;
+pub mod record;
+pub mod rules;
+pub mod solver;
+pub mod topology;
+pub mod variants;
+
+#[cfg(test)]
+mod testing;
@@ -0,0 +1,60 @@
+use crate::analysis::Impossibility;
+use crate::level::Level;
+use crate::notation;
+use crate::position::{Position, Shape};
+use crate::solver::Solver;
+use std::fmt;
+
+// Problems with a level that parses but cannot be played as intended
+#[derive(Debug, Clone, PartialEq)]
+pub enum Lint {
+    // No jump is possible from the starting position
+    NoOpeningJump,
+    // A slot that no jump starts from, passes over or lands on
+    DeadSlot { x: usize, y: usize },
+    // An invariant proves that the goal can never be reached
+    Unwinnable(Impossibility),
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Lint::NoOpeningJump => write!(f, "no jump is possible from the start"),
+            Lint::DeadSlot { x, y } => write!(
+                f,
+                "slot {} is not part of any jump",
+                notation::slot_name(*x, *y)
+            ),
+            Lint::Unwinnable(reason) => write!(f, "the goal cannot be reached: {}", reason),
+        }
+    }
+}
+
+// Checks a level for problems. The checks rely on invariants rather than a search, so a level
+// without lints may still have no solution.
+pub fn lint(level: &Level) -> Vec<Lint> {
+    let shape = match Shape::from_level(level) {
+        Some(shape) => shape,
+        None => return Vec::new(),
+    };
+    let start = Position::from_level(&shape, level);
+
+    let mut lints = Vec::new();
+    if start.legal_jumps(&shape).next().is_none() {
+        lints.push(Lint::NoOpeningJump);
+    }
+
+    let used = shape.jumps().iter().fold(0u128, |used, j| used | j.mask);
+    for (hole, (x, y)) in shape.holes().iter().copied().enumerate() {
+        if used & 1 << hole == 0 {
+            lints.push(Lint::DeadSlot { x, y });
+        }
+    }
+
+    let reason = Solver::new(shape, level.goal, start).and_then(|solver| solver.explain());
+    if let Some(reason) = reason {
+        lints.push(Lint::Unwinnable(reason));
+    }
+
+    lints
+}
@@ -0,0 +1,98 @@
+// Slots are named by a column letter followed by a row number, e.g. `d4`. Columns run from `a` on
+// the left through `z`, then continue with `aa`, `ab`, ... on wider boards. Rows are numbered from
+// 1 at the top. A jump is written as the two slots joined by a dash, e.g. `d2-d4`, and a chain of
+// jumps by the same peg lists every landing slot, e.g. `d2-d4-f4`.
+
+pub fn slot_name(x: usize, y: usize) -> String {
+    let mut column = Vec::new();
+    let mut n = x + 1;
+    while n > 0 {
+        n -= 1;
+        column.push((b'a' + (n % 26) as u8) as char);
+        n /= 26;
+    }
+
+    column.iter().rev().collect::<String>() + &(y + 1).to_string()
+}
+
+pub fn parse_slot(s: &str) -> Option<(usize, usize)> {
+    let split = s.find(|c: char| !c.is_ascii_lowercase())?;
+    let (column, row) = s.split_at(split);
+    if column.is_empty() {
+        return None;
+    }
+
+    let mut x = 0usize;
+    for c in column.bytes() {
+        x = x.checked_mul(26)?.checked_add((c - b'a') as usize + 1)?;
+    }
+
+    if !row.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let y: usize = row.parse().ok()?;
+    if y == 0 {
+        return None;
+    }
+
+    Some((x - 1, y - 1))
+}
+
+pub fn path_name(path: &[(usize, usize)]) -> String {
+    path.iter()
+        .map(|(x, y)| slot_name(*x, *y))
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+// Parses a jump or a chain of jumps, which must visit at least two slots
+pub fn parse_path(s: &str) -> Option<Vec<(usize, usize)>> {
+    let path = s.split('-').map(parse_slot).collect::<Option<Vec<_>>>()?;
+    if path.len() < 2 {
+        return None;
+    }
+
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slot_names_round_trip() {
+        let slots = [
+            (0, 0),
+            (3, 3),
+            (25, 0),
+            (26, 1),
+            (27, 9),
+            (701, 99),
+            (702, 0),
+        ];
+        for (x, y) in slots.iter().copied() {
+            let name = slot_name(x, y);
+            assert_eq!(parse_slot(&name), Some((x, y)), "slot `{}`", name);
+        }
+
+        assert_eq!(slot_name(3, 3), "d4");
+        assert_eq!(slot_name(26, 0), "aa1");
+        assert_eq!(slot_name(702, 0), "aaa1");
+    }
+
+    #[test]
+    fn invalid_slots_are_rejected() {
+        for s in ["", "d", "4", "d0", "D4", "d4x", "d-4"].iter() {
+            assert_eq!(parse_slot(s), None, "slot `{}`", s);
+        }
+    }
+
+    #[test]
+    fn paths_round_trip() {
+        let path = vec![(3, 1), (3, 3), (5, 3)];
+        assert_eq!(path_name(&path), "d2-d4-f4");
+        assert_eq!(parse_path("d2-d4-f4"), Some(path));
+        assert_eq!(parse_path("d2"), None);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::level::Level;
+use crate::notation;
+use crate::variants::{self, UnknownVariant};
+use std::fmt;
+
+// How to set up the board, as chosen on the command line of a front-end
+#[derive(Debug, Default)]
+pub struct BoardOptions {
+    pub variant: Option<String>,
+    pub start: Option<(usize, usize)>,
+    pub diagonal: bool,
+    pub chains: bool,
+}
+
+// Index of the level to play, and the slot to leave empty at the start if not the usual one
+pub type BoardChoice = (usize, Option<(usize, usize)>);
+
+impl BoardOptions {
+    // Applies the options to the levels of the catalogue and returns the board to play
+    pub fn pick_board(&self, levels: &mut [Level]) -> Result<BoardChoice, BoardError> {
+        levels.iter_mut().for_each(|level| self.apply_rules(level));
+
+        let name = self.variant.as_deref().unwrap_or(variants::DEFAULT_VARIANT);
+        let current = variants::find(levels, name).map_err(BoardError::UnknownVariant)?;
+        check_start(&levels[current], self.start)?;
+        Ok((current, self.start))
+    }
+
+    // Applies the rules and the start to a level loaded from a file, which is the board to play.
+    // The variant does not apply to it.
+    pub fn apply_to_level(&self, level: &mut Level) -> Result<(), BoardError> {
+        self.apply_rules(level);
+        check_start(level, self.start)
+    }
+
+    fn apply_rules(&self, level: &mut Level) {
+        level.rules.diagonal_jumps |= self.diagonal;
+        level.rules.chain_jumps |= self.chains;
+    }
+}
+
+fn check_start(level: &Level, start: Option<(usize, usize)>) -> Result<(), BoardError> {
+    match start {
+        Some((x, y)) if level.with_start(x, y).is_none() => Err(BoardError::StartNotOnBoard(x, y)),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Debug)]
+pub enum BoardError {
+    UnknownVariant(UnknownVariant),
+    // The slot to leave empty at the start is not a slot of the board
+    StartNotOnBoard(usize, usize),
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoardError::UnknownVariant(e) => write!(f, "{}", e),
+            BoardError::StartNotOnBoard(x, y) => {
+                write!(
+                    f,
+                    "slot {} is not on the board",
+                    notation::slot_name(*x, *y)
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for BoardError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_board_and_checks_the_start() {
+        let mut levels: Vec<Level> = variants::VARIANTS.iter().map(|v| v.level()).collect();
+        let board = BoardOptions {
+            variant: Some("French".to_string()),
+            diagonal: true,
+            ..BoardOptions::default()
+        };
+        let (current, start) = board.pick_board(&mut levels).unwrap();
+        assert_eq!(levels[current].name, "French");
+        assert_eq!(start, None);
+        assert!(levels.iter().all(|level| level.rules.diagonal_jumps));
+
+        let board = BoardOptions {
+            variant: Some("nowhere".to_string()),
+            ..BoardOptions::default()
+        };
+        assert!(matches!(
+            board.pick_board(&mut levels),
+            Err(BoardError::UnknownVariant(_))
+        ));
+
+        let board = BoardOptions {
+            start: Some((0, 0)),
+            ..BoardOptions::default()
+        };
+        assert!(matches!(
+            board.pick_board(&mut levels),
+            Err(BoardError::StartNotOnBoard(0, 0))
+        ));
+    }
+
+    #[test]
+    fn applies_the_rules_and_start_to_a_level_file() {
+        let mut level = variants::VARIANTS[0].level();
+        let board = BoardOptions {
+            start: Some((3, 3)),
+            chains: true,
+            ..BoardOptions::default()
+        };
+        assert!(board.apply_to_level(&mut level).is_ok());
+        assert!(level.rules.chain_jumps && !level.rules.diagonal_jumps);
+
+        let board = BoardOptions {
+            start: Some((0, 0)),
+            ..BoardOptions::default()
+        };
+        assert!(board.apply_to_level(&mut level).is_err());
+    }
+}
@@ -0,0 +1,250 @@
+use crate::level::Level;
+use crate::rules::Rules;
+use crate::topology::{Direction, Topology};
+
+// Bitboard representation of the game, which is the source of truth for the rules.
+//
+// The holes of a board are numbered row by row and a position is the set of holes holding a peg,
+// stored in the bits of a `u128`. Every jump the board allows is precomputed as a mask of its three
+// holes, so checking a jump is a single comparison and playing or taking it back a single XOR.
+// Positions also carry a Zobrist hash, which is updated along with the pegs.
+pub const MAX_HOLES: usize = 128;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Jump {
+    pub from: usize,
+    pub over: usize,
+    pub to: usize,
+    // Bits of the three holes
+    pub mask: u128,
+    // Change to the Zobrist hash of a position when the jump is played
+    key: u64,
+}
+
+// The holes of a board and the jumps between them
+#[derive(Debug, Clone)]
+pub struct Shape {
+    topology: Topology,
+    rules: Rules,
+    width: usize,
+    height: usize,
+    holes: Vec<(usize, usize)>,
+    // Hole number of each slot of the bounding box, row by row
+    index: Vec<Option<usize>>,
+    jumps: Vec<Jump>,
+    zobrist: Vec<u64>,
+}
+
+impl Shape {
+    // Returns `None` if there are no holes or more than `MAX_HOLES`
+    pub fn new(topology: Topology, rules: Rules, holes: &[(usize, usize)]) -> Option<Self> {
+        if holes.is_empty() || holes.len() > MAX_HOLES {
+            return None;
+        }
+
+        let mut holes = holes.to_vec();
+        holes.sort_by_key(|(x, y)| (*y, *x));
+        holes.dedup();
+
+        let width = holes.iter().map(|(x, _)| x + 1).max().unwrap_or(0);
+        let height = holes.iter().map(|(_, y)| y + 1).max().unwrap_or(0);
+        let mut index = vec![None; width * height];
+        for (i, (x, y)) in holes.iter().enumerate() {
+            index[y * width + x] = Some(i);
+        }
+
+        let zobrist = (0..holes.len() as u64).map(splitmix64).collect();
+
+        let mut shape = Self {
+            topology,
+            rules,
+            width,
+            height,
+            holes,
+            index,
+            jumps: Vec::new(),
+            zobrist,
+        };
+
+        for (from, (x, y)) in shape.holes.iter().enumerate() {
+            for (dx, dy) in rules.directions(topology) {
+                let over = shape.hole_at_offset(*x, *y, *dx, *dy);
+                let to = shape.hole_at_offset(*x, *y, dx * 2, dy * 2);
+                if let (Some(over), Some(to)) = (over, to) {
+                    shape.jumps.push(Jump {
+                        from,
+                        over,
+                        to,
+                        mask: 1 << from | 1 << over | 1 << to,
+                        key: shape.zobrist[from] ^ shape.zobrist[over] ^ shape.zobrist[to],
+                    });
+                }
+            }
+        }
+
+        Some(shape)
+    }
+
+    pub fn from_level(level: &Level) -> Option<Self> {
+        Self::new(level.topology, level.rules, &level.holes())
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    // Size of the bounding box of the holes
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn holes(&self) -> &[(usize, usize)] {
+        &self.holes
+    }
+
+    // Slot coordinates of a hole
+    pub fn hole(&self, hole: usize) -> (usize, usize) {
+        self.holes[hole]
+    }
+
+    pub fn hole_at(&self, x: usize, y: usize) -> Option<usize> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        self.index[y * self.width + x]
+    }
+
+    // Neighbouring slot the cursor moves to in the given direction
+    pub fn slot_towards(&self, x: usize, y: usize, direction: Direction) -> Option<(usize, usize)> {
+        self.rules
+            .cursor_offsets(self.topology, direction)
+            .iter()
+            .find_map(|(dx, dy)| self.hole_at_offset(x, y, *dx, *dy))
+            .map(|hole| self.hole(hole))
+    }
+
+    fn hole_at_offset(&self, x: usize, y: usize, dx: isize, dy: isize) -> Option<usize> {
+        let x = x as isize + dx;
+        let y = y as isize + dy;
+        if x < 0 || y < 0 {
+            return None;
+        }
+
+        self.hole_at(x as usize, y as usize)
+    }
+
+    pub fn jumps(&self) -> &[Jump] {
+        &self.jumps
+    }
+
+    // The jump from one hole to another, if the board has one
+    pub fn jump_between(&self, from: usize, to: usize) -> Option<Jump> {
+        self.jumps
+            .iter()
+            .find(|j| j.from == from && j.to == to)
+            .copied()
+    }
+
+    pub fn zobrist(&self, hole: usize) -> u64 {
+        self.zobrist[hole]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pegs: u128,
+    hash: u64,
+}
+
+impl Position {
+    pub fn new(shape: &Shape, pegs: &[(usize, usize)]) -> Self {
+        let bits = pegs
+            .iter()
+            .filter_map(|(x, y)| shape.hole_at(*x, *y))
+            .fold(0u128, |bits, i| bits | 1 << i);
+
+        Self::from_bits(shape, bits)
+    }
+
+    pub fn from_level(shape: &Shape, level: &Level) -> Self {
+        Self::new(shape, &level.pegs())
+    }
+
+    pub fn from_bits(shape: &Shape, pegs: u128) -> Self {
+        let hash = (0..shape.holes().len())
+            .filter(|i| pegs & 1 << i != 0)
+            .fold(0, |hash, i| hash ^ shape.zobrist(i));
+
+        Self { pegs, hash }
+    }
+
+    pub fn bits(&self) -> u128 {
+        self.pegs
+    }
+
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    pub fn count(&self) -> u16 {
+        self.pegs.count_ones() as u16
+    }
+
+    pub fn is_occupied(&self, hole: usize) -> bool {
+        self.pegs & 1 << hole != 0
+    }
+
+    // Holes holding a peg
+    pub fn pegs(&self) -> impl Iterator<Item = usize> {
+        let mut bits = self.pegs;
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+
+            let hole = bits.trailing_zeros() as usize;
+            bits &= bits - 1;
+            Some(hole)
+        })
+    }
+
+    pub fn is_legal(&self, jump: &Jump) -> bool {
+        self.pegs & jump.mask == 1 << jump.from | 1 << jump.over
+    }
+
+    pub fn legal_jumps<'a>(&self, shape: &'a Shape) -> impl Iterator<Item = Jump> + 'a {
+        let position = *self;
+        shape
+            .jumps()
+            .iter()
+            .filter(move |j| position.is_legal(j))
+            .copied()
+    }
+
+    pub fn apply(&mut self, jump: &Jump) {
+        self.pegs ^= jump.mask;
+        self.hash ^= jump.key;
+    }
+
+    // Takes back a jump, which flips the same three holes as playing it
+    pub fn undo(&mut self, jump: &Jump) {
+        self.apply(jump);
+    }
+}
+
+// Fixed pseudo-random keys, so that hashes are the same from one run to the next
+fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
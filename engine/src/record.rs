@@ -0,0 +1,317 @@
+use crate::file::{self, FileError, Header};
+use crate::game::{Game, GameStatus};
+use crate::level::Level;
+use crate::notation;
+use crate::rules::Rules;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub use crate::file::{ParseError, ParseErrorKind};
+
+// Game records use the same layout as level files: a header of `key: value` lines, followed by a
+// blank line and the numbered list of moves in slot notation (see `notation`). `variant` names a
+// built-in variant or a level, `rules` lists the optional rules the game was played with and
+// `start` names the empty slot when the game did not start from the usual position.
+// Move numbers are optional when reading a record.
+//
+// Example:
+//   variant: English
+//   date: 2026-10-18
+//   result: unfinished
+//
+//   1. d2-d4
+//   2. f3-d3
+//   3. e1-e3
+
+pub const RECORD_EXTENSION: &str = "rec";
+
+#[derive(Debug, Clone)]
+pub struct GameRecord {
+    pub variant: String,
+    pub date: String,
+    pub result: String,
+    pub rules: Rules,
+    // Slot left empty at the start, if the game started with pegs in every other slot
+    pub start: Option<(usize, usize)>,
+    // Each move is the path of a peg, which visits more than two slots for chained jumps
+    pub moves: Vec<Vec<(usize, usize)>>,
+}
+
+impl GameRecord {
+    // Records the game played so far on the level, started with `start` left empty if it was not
+    // started from the usual position
+    pub fn from_game(game: &Game, level: &Level, start: Option<(usize, usize)>) -> Self {
+        let position = |hole| game.shape().hole(hole);
+
+        let mut moves: Vec<Vec<(usize, usize)>> = Vec::new();
+        for entry in game.history().entries() {
+            match moves.last_mut() {
+                Some(path) if entry.chained => path.push(position(entry.jump.to)),
+                _ => moves.push(vec![position(entry.jump.from), position(entry.jump.to)]),
+            }
+        }
+
+        let result = match game.status() {
+            GameStatus::OnGoing => "unfinished".to_string(),
+            GameStatus::Solved => "solved".to_string(),
+            GameStatus::Completed if game.peg_count() == 1 => "1 peg left".to_string(),
+            GameStatus::Completed => format!("{} pegs left", game.peg_count()),
+        };
+
+        Self {
+            variant: level.name.clone(),
+            date: today(),
+            result,
+            rules: game.rules(),
+            start,
+            moves,
+        }
+    }
+
+    pub fn jump_count(&self) -> usize {
+        self.moves.iter().map(|path| path.len() - 1).sum()
+    }
+
+    // Plays the recorded moves in the game, which must have been started on the recorded variant.
+    // The moves stay in the history to be undone.
+    pub fn replay(&self, game: &mut Game) -> Result<(), ReplayError> {
+        for (index, path) in self.moves.iter().enumerate() {
+            for jump in path.windows(2) {
+                if game.play(jump[0], jump[1]).is_none() {
+                    return Err(ReplayError {
+                        move_number: index + 1,
+                        path: notation::path_name(path),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "variant: {}", self.variant)?;
+        writeln!(f, "date: {}", self.date)?;
+        writeln!(f, "result: {}", self.result)?;
+        if self.rules != Rules::default() {
+            writeln!(f, "rules: {}", self.rules)?;
+        }
+        if let Some((x, y)) = self.start {
+            writeln!(f, "start: {}", notation::slot_name(x, y))?;
+        }
+
+        writeln!(f)?;
+        for (index, path) in self.moves.iter().enumerate() {
+            writeln!(f, "{}. {}", index + 1, notation::path_name(path))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = file::lines(s);
+
+        // header
+        let mut header =
+            Header::parse(&mut lines, &["variant", "date", "result", "rules", "start"])?;
+        let variant = header.required("variant")?;
+        let date = header.optional("date");
+        let result = header.optional("result");
+        let rules = header.take("rules");
+        let start = header.take("start");
+
+        let rules = match rules {
+            None => Rules::default(),
+            Some((line_no, col, value)) => value
+                .parse()
+                .map_err(|_| ParseError::new(line_no, col, ParseErrorKind::InvalidRules(value)))?,
+        };
+        let start = match start {
+            None => None,
+            Some((line_no, col, value)) => Some(notation::parse_slot(&value).ok_or_else(|| {
+                ParseError::new(line_no, col, ParseErrorKind::InvalidStart(value))
+            })?),
+        };
+
+        // moves
+        let mut moves = Vec::new();
+        for (line_no, line) in lines {
+            for token in line.split_whitespace() {
+                // skip move numbers
+                if token
+                    .strip_suffix('.')
+                    .is_some_and(|n| n.parse::<usize>().is_ok())
+                {
+                    continue;
+                }
+
+                let column = token.as_ptr() as usize - line.as_ptr() as usize + 1;
+                let path = notation::parse_path(token).ok_or_else(|| {
+                    ParseError::new(
+                        line_no,
+                        column,
+                        ParseErrorKind::InvalidMove(token.to_string()),
+                    )
+                })?;
+                moves.push(path);
+            }
+        }
+
+        Ok(Self {
+            variant,
+            date,
+            result,
+            rules,
+            start,
+            moves,
+        })
+    }
+}
+
+// A recorded move that is not legal in the replayed game
+#[derive(Debug)]
+pub struct ReplayError {
+    pub move_number: usize,
+    pub path: String,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {}: illegal move `{}`", self.move_number, self.path)
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+pub type RecordError = FileError;
+
+pub fn load_record<P: AsRef<Path>>(path: P) -> Result<GameRecord, RecordError> {
+    file::load(path)
+}
+
+// Writes the record into the given directory, named after the variant and the current time
+pub fn save_record<P: AsRef<Path>>(dir: P, record: &GameRecord) -> Result<PathBuf, RecordError> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir).map_err(|e| RecordError::Io(dir.to_owned(), e))?;
+
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let name = record.variant.to_lowercase().replace(' ', "-");
+    let path = dir.join(format!("{}-{}.{}", name, seconds, RECORD_EXTENSION));
+
+    fs::write(&path, record.to_string()).map_err(|e| RecordError::Io(path.clone(), e))?;
+    Ok(path)
+}
+
+// Current UTC date as `YYYY-MM-DD`
+fn today() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    // civil date from days since the epoch, see http://howardhinnant.github.io/date_algorithms.html
+    let z = (seconds / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::parse_error;
+
+    #[test]
+    fn records_round_trip() {
+        let source = "\
+variant: English
+date: 2026-10-18
+result: unfinished
+rules: chains
+start: d2
+
+1. d4-d2
+2. f3-d3-d1
+";
+        let record: GameRecord = source.parse().unwrap();
+        assert_eq!(record.start, Some((3, 1)));
+        assert!(record.rules.chain_jumps);
+        assert_eq!(record.moves.len(), 2);
+        assert_eq!(record.jump_count(), 3);
+        assert_eq!(record.to_string(), source);
+    }
+
+    #[test]
+    fn move_numbers_are_optional() {
+        let record: GameRecord = "variant: English\n\nd2-d4 f3-d3\n".parse().unwrap();
+        assert_eq!(
+            record.moves,
+            vec![vec![(3, 1), (3, 3)], vec![(5, 2), (3, 2)]]
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_line_and_column() {
+        assert_eq!(
+            parse_error::<GameRecord>("date: 2026-10-18\n\n1. d2-d4\n"),
+            ParseError::new(1, 1, ParseErrorKind::MissingKey("variant"))
+        );
+        assert_eq!(
+            parse_error::<GameRecord>("variant: English\nplayer: Someone\n\n1. d2-d4\n"),
+            ParseError::new(2, 1, ParseErrorKind::UnknownKey("player".to_string()))
+        );
+        assert_eq!(
+            parse_error::<GameRecord>("variant: English\nstart: 4d\n\n1. d2-d4\n"),
+            ParseError::new(2, 8, ParseErrorKind::InvalidStart("4d".to_string()))
+        );
+        assert_eq!(
+            parse_error::<GameRecord>("variant: English\n\n1. d2-d4\n2.  f3-\n"),
+            ParseError::new(4, 5, ParseErrorKind::InvalidMove("f3-".to_string()))
+        );
+    }
+
+    #[test]
+    fn replay_rejects_slots_outside_the_board() {
+        let record: GameRecord = "variant: English\n\n1. d2-d4\n2. d9999999999999999999-d4\n"
+            .parse()
+            .unwrap();
+        let mut game = crate::testing::variant("english").start_game(None);
+        let error = record.replay(&mut game).unwrap_err();
+        assert_eq!(error.move_number, 2);
+        assert_eq!(game.move_count(), 1);
+    }
+
+    #[test]
+    fn records_keep_the_start_slot() {
+        let level = crate::testing::variant("english");
+        let mut game = level.start_game(Some((3, 1)));
+        assert!(game.play((3, 3), (3, 1)).is_some());
+
+        let record = GameRecord::from_game(&game, &level, Some((3, 1)));
+        let saved: GameRecord = record.to_string().parse().unwrap();
+        assert_eq!(saved.start, Some((3, 1)));
+
+        let mut replayed = level.start_game(saved.start);
+        assert!(saved.replay(&mut replayed).is_ok());
+        assert_eq!(replayed.position(), game.position());
+    }
+}
@@ -0,0 +1,79 @@
+use crate::topology::{Direction, Topology};
+use std::fmt;
+use std::str::FromStr;
+
+const ALL_SQUARE_DIRECTIONS: [(isize, isize); 8] = [
+    (0, -1),
+    (0, 1),
+    (-1, 0),
+    (1, 0),
+    (-1, -1),
+    (1, -1),
+    (-1, 1),
+    (1, 1),
+];
+
+// Optional rule variations chosen per game
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rules {
+    // Pegs may also jump diagonally on square boards. Triangular boards already jump in all six
+    // directions, so this has no effect on them.
+    pub diagonal_jumps: bool,
+    // Consecutive jumps by the same peg count as a single move
+    pub chain_jumps: bool,
+}
+
+impl Rules {
+    // Unit offsets along which a peg may jump
+    pub fn directions(&self, topology: Topology) -> &'static [(isize, isize)] {
+        match topology {
+            Topology::Square if self.diagonal_jumps => &ALL_SQUARE_DIRECTIONS,
+            _ => topology.directions(),
+        }
+    }
+
+    // Offsets to try, in order, when moving the cursor in the given direction
+    pub fn cursor_offsets(
+        &self,
+        topology: Topology,
+        direction: Direction,
+    ) -> &'static [(isize, isize)] {
+        if topology == Topology::Square && direction.is_diagonal() && !self.diagonal_jumps {
+            return &[];
+        }
+
+        topology.cursor_offsets(direction)
+    }
+}
+
+impl fmt::Display for Rules {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut names = Vec::new();
+        if self.diagonal_jumps {
+            names.push("diagonal");
+        }
+        if self.chain_jumps {
+            names.push("chains");
+        }
+
+        write!(f, "{}", names.join(", "))
+    }
+}
+
+// Rules are written as a comma separated list, e.g. `rules: diagonal, chains`
+impl FromStr for Rules {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rules = Rules::default();
+        for rule in s.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+            match rule {
+                "diagonal" => rules.diagonal_jumps = true,
+                "chains" => rules.chain_jumps = true,
+                _ => return Err(()),
+            }
+        }
+
+        Ok(rules)
+    }
+}
@@ -0,0 +1,424 @@
+use crate::analysis::{Analysis, Impossibility};
+use crate::game::Game;
+use crate::level::Goal;
+use crate::position::{Jump, Position, Shape};
+use crate::topology::Topology;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::hash::{BuildHasherDefault, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Depth-first search for a sequence of jumps that reaches the goal.
+//
+// Positions that the invariants in `analysis` prove lost are not searched any further.
+//
+// Positions that are known to fail are remembered in a transposition table, keyed by the smallest
+// Zobrist hash of their symmetric images so that a failure found in one corner of the board is not
+// searched again in another. The hash of every image is updated along with the position, one XOR
+// per jump. Two positions sharing a 64-bit key is unlikely enough to be ignored.
+
+// Boards have at most 8 symmetries
+const MAX_SYMMETRIES: usize = 8;
+
+// Positions a search run in the background may visit before giving up. Solving the French board
+// from its usual start takes about 7 million of them; the German and Diamond boards need far more.
+// Using up the budget takes 5 to 11 seconds in a release build, about three times as long in a
+// debug build, and about 60 MB.
+pub const SEARCH_BUDGET: usize = 10_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlotJump {
+    pub from: (usize, usize),
+    pub over: (usize, usize),
+    pub to: (usize, usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct Solution {
+    pub jumps: Vec<SlotJump>,
+    chain_jumps: bool,
+}
+
+impl Solution {
+    // Paths of the pegs moved, with chained jumps grouped into a single move if the rules allow it
+    pub fn moves(&self) -> Vec<Vec<(usize, usize)>> {
+        let mut moves: Vec<Vec<(usize, usize)>> = Vec::new();
+        for jump in &self.jumps {
+            match moves.last_mut() {
+                Some(path) if self.chain_jumps && path.last() == Some(&jump.from) => {
+                    path.push(jump.to)
+                }
+                _ => moves.push(vec![jump.from, jump.to]),
+            }
+        }
+
+        moves
+    }
+}
+
+// Why a search was given up before it finished
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interrupted {
+    Cancelled,
+    // The search visited as many positions as it was allowed to
+    OutOfBudget,
+}
+
+// Limits on a search. Every position visited is remembered at most once, so the budget bounds the
+// memory taken by the transposition table as well as the time.
+struct Limits<'a> {
+    cancel: &'a AtomicBool,
+    positions_left: usize,
+}
+
+impl Limits<'_> {
+    // Accounts for one more position searched
+    fn visit(&mut self) -> Result<(), Interrupted> {
+        if self.cancel.load(Ordering::Relaxed) {
+            return Err(Interrupted::Cancelled);
+        }
+
+        self.positions_left = self
+            .positions_left
+            .checked_sub(1)
+            .ok_or(Interrupted::OutOfBudget)?;
+        Ok(())
+    }
+}
+
+// The keys of the transposition table are hashes already
+#[derive(Default)]
+struct IdentityHasher(u64);
+
+impl Hasher for IdentityHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, _bytes: &[u8]) {
+        unreachable!("only u64 keys are hashed");
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.0 = n;
+    }
+}
+
+type FailedPositions = HashSet<u64, BuildHasherDefault<IdentityHasher>>;
+
+// Hashes of the images of a position under each symmetry
+type Images = [u64; MAX_SYMMETRIES];
+
+pub struct Solver {
+    shape: Shape,
+    // Number of permutations of the holes that map the board, its jumps and the goal onto
+    // themselves
+    symmetries: usize,
+    // Change to the hash of every image of a position when a jump is played, by jump
+    jump_keys: Vec<Images>,
+    // Hole the last peg must end on
+    target: Option<usize>,
+    // Invariants used to cut off positions that cannot reach the goal
+    analysis: Analysis,
+    start: Position,
+    start_images: Images,
+}
+
+impl Solver {
+    // Sets up a search on the board from the given position. Returns `None` if the goal is not a
+    // hole of the board.
+    pub fn new(shape: Shape, goal: Goal, start: Position) -> Option<Self> {
+        let target = match goal {
+            Goal::SinglePeg => None,
+            Goal::SinglePegAt { x, y } => Some(shape.hole_at(x, y)?),
+        };
+
+        let permutations: Vec<Vec<usize>> = transforms(shape.topology(), shape.holes())
+            .into_iter()
+            .filter_map(|transform| {
+                shape
+                    .holes()
+                    .iter()
+                    .map(|h| transform(*h).and_then(|(x, y)| shape.hole_at(x, y)))
+                    .collect::<Option<Vec<usize>>>()
+            })
+            .filter(|perm| target.is_none_or(|t| perm[t] == t))
+            .take(MAX_SYMMETRIES)
+            .collect();
+
+        let jump_keys = shape
+            .jumps()
+            .iter()
+            .map(|jump| {
+                let mut keys = [0; MAX_SYMMETRIES];
+                for (key, perm) in keys.iter_mut().zip(&permutations) {
+                    *key = [jump.from, jump.over, jump.to]
+                        .iter()
+                        .fold(0, |key, hole| key ^ shape.zobrist(perm[*hole]));
+                }
+                keys
+            })
+            .collect();
+
+        let mut start_images = [0; MAX_SYMMETRIES];
+        for (image, perm) in start_images.iter_mut().zip(&permutations) {
+            *image = start
+                .pegs()
+                .fold(0, |hash, hole| hash ^ shape.zobrist(perm[hole]));
+        }
+
+        Some(Self {
+            analysis: Analysis::new(&shape),
+            symmetries: permutations.len(),
+            jump_keys,
+            target,
+            start,
+            start_images,
+            shape,
+        })
+    }
+
+    // Sets up a search from the current position of the game
+    pub fn from_game(game: &Game) -> Option<Self> {
+        Self::new(game.shape().clone(), game.goal(), game.position())
+    }
+
+    // Proves that the goal cannot be reached from the starting position, if an invariant says so
+    pub fn explain(&self) -> Option<Impossibility> {
+        self.analysis.explain(self.start.bits(), self.target)
+    }
+
+    pub fn solve(&self) -> Option<Solution> {
+        self.solve_cancellable(&AtomicBool::new(false), usize::MAX)
+            .unwrap_or_default()
+    }
+
+    // Like `solve`, but gives up as soon as `cancel` is set or after searching `budget` positions
+    pub fn solve_cancellable(
+        &self,
+        cancel: &AtomicBool,
+        budget: usize,
+    ) -> Result<Option<Solution>, Interrupted> {
+        let mut limits = Limits {
+            cancel,
+            positions_left: budget,
+        };
+        let mut failed = FailedPositions::default();
+        let mut path = Vec::new();
+
+        if !self.search(
+            self.start,
+            self.start_images,
+            &mut limits,
+            &mut failed,
+            &mut path,
+        )? {
+            return Ok(None);
+        }
+
+        let jumps = path
+            .iter()
+            .map(|j: &Jump| SlotJump {
+                from: self.shape.hole(j.from),
+                over: self.shape.hole(j.over),
+                to: self.shape.hole(j.to),
+            })
+            .collect();
+
+        Ok(Some(Solution {
+            jumps,
+            chain_jumps: self.shape.rules().chain_jumps,
+        }))
+    }
+
+    fn search(
+        &self,
+        position: Position,
+        images: Images,
+        limits: &mut Limits,
+        failed: &mut FailedPositions,
+        path: &mut Vec<Jump>,
+    ) -> Result<bool, Interrupted> {
+        let pegs = position.bits();
+        if position.count() == 1 {
+            return Ok(self.target.is_none_or(|t| pegs == 1 << t));
+        }
+
+        limits.visit()?;
+
+        if self.analysis.is_lost(pegs, self.target) {
+            return Ok(false);
+        }
+
+        let key = images[..self.symmetries]
+            .iter()
+            .copied()
+            .min()
+            .unwrap_or(position.hash());
+        if failed.contains(&key) {
+            return Ok(false);
+        }
+
+        for (jump, keys) in self.shape.jumps().iter().zip(&self.jump_keys) {
+            if !position.is_legal(jump) {
+                continue;
+            }
+
+            let mut next = position;
+            next.apply(jump);
+            let mut next_images = images;
+            for (image, key) in next_images.iter_mut().zip(keys) {
+                *image ^= key;
+            }
+
+            path.push(*jump);
+            if self.search(next, next_images, limits, failed, path)? {
+                return Ok(true);
+            }
+            path.pop();
+        }
+
+        failed.insert(key);
+        Ok(false)
+    }
+}
+
+// Slot in the middle of a square board, if the board has one
+pub fn centre(shape: &Shape) -> Option<(usize, usize)> {
+    let holes = shape.holes();
+    if shape.topology() != Topology::Square {
+        return None;
+    }
+
+    let (min_x, max_x) = bounds(holes.iter().map(|(x, _)| *x));
+    let (min_y, max_y) = bounds(holes.iter().map(|(_, y)| *y));
+    if (min_x + max_x) % 2 != 0 || (min_y + max_y) % 2 != 0 {
+        return None;
+    }
+
+    let centre = ((min_x + max_x) / 2, (min_y + max_y) / 2);
+    holes.contains(&centre).then_some(centre)
+}
+
+type Transform = Box<dyn Fn((usize, usize)) -> Option<(usize, usize)>>;
+type DihedralMap = fn(isize, isize) -> (isize, isize);
+
+// Candidate symmetries of the bounding box of the holes. Those that do not map the holes onto
+// themselves are discarded by the caller.
+fn transforms(topology: Topology, holes: &[(usize, usize)]) -> Vec<Transform> {
+    match topology {
+        Topology::Square => {
+            // reflections and turns about the centre of the bounding box, working on doubled
+            // coordinates so that the centre may fall between two slots
+            let (min_x, max_x) = bounds(holes.iter().map(|(x, _)| *x));
+            let (min_y, max_y) = bounds(holes.iter().map(|(_, y)| *y));
+            let (sx, sy) = ((min_x + max_x) as isize, (min_y + max_y) as isize);
+            let square = max_x - min_x == max_y - min_y;
+
+            let maps: [DihedralMap; 8] = [
+                |u, v| (u, v),
+                |u, v| (-u, v),
+                |u, v| (u, -v),
+                |u, v| (-u, -v),
+                |u, v| (v, u),
+                |u, v| (-v, u),
+                |u, v| (v, -u),
+                |u, v| (-v, -u),
+            ];
+
+            // quarter turns and diagonal reflections need a square bounding box
+            let count = if square { 8 } else { 4 };
+            maps[..count]
+                .iter()
+                .map(|map| {
+                    let map = *map;
+                    Box::new(move |(x, y): (usize, usize)| {
+                        let (u, v) = map(2 * x as isize - sx, 2 * y as isize - sy);
+                        let x = usize::try_from((u + sx) / 2).ok()?;
+                        let y = usize::try_from((v + sy) / 2).ok()?;
+                        Some((x, y))
+                    }) as Transform
+                })
+                .collect()
+        }
+        Topology::Triangular => {
+            // a slot is described by its distances to the three sides of the triangle, which
+            // each symmetry permutes
+            const PERMUTATIONS: [[usize; 3]; 6] = [
+                [0, 1, 2],
+                [0, 2, 1],
+                [1, 0, 2],
+                [1, 2, 0],
+                [2, 0, 1],
+                [2, 1, 0],
+            ];
+
+            let (_, h) = bounds(holes.iter().map(|(_, y)| *y));
+            PERMUTATIONS
+                .iter()
+                .map(|p| {
+                    let p = *p;
+                    Box::new(move |(x, y): (usize, usize)| {
+                        let sides = [y.checked_sub(x)?, x, h.checked_sub(y)?];
+                        Some((sides[p[1]], h - sides[p[2]]))
+                    }) as Transform
+                })
+                .collect()
+        }
+    }
+}
+
+fn bounds(values: impl Iterator<Item = usize>) -> (usize, usize) {
+    values.fold((usize::MAX, 0), |(min, max), v| (min.min(v), max.max(v)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameStatus;
+    use crate::testing::variant;
+
+    // Plays the solution on the variant and returns how the game ends
+    fn solve(key: &str) -> GameStatus {
+        let mut game = Game::new(&variant(key)).unwrap();
+        let solution = Solver::from_game(&game).unwrap().solve().unwrap();
+
+        for jump in &solution.jumps {
+            assert!(
+                game.play(jump.from, jump.to).is_some(),
+                "illegal {:?}",
+                jump
+            );
+        }
+        game.status()
+    }
+
+    #[test]
+    fn solves_the_english_board_to_the_centre() {
+        assert_eq!(solve("english"), GameStatus::Solved);
+    }
+
+    #[test]
+    fn solves_the_triangular_board() {
+        assert_eq!(solve("triangular"), GameStatus::Solved);
+    }
+
+    #[test]
+    fn gives_up_when_cancelled_or_out_of_budget() {
+        let game = Game::new(&variant("english")).unwrap();
+        let solver = Solver::from_game(&game).unwrap();
+
+        let result = solver.solve_cancellable(&AtomicBool::new(false), 10);
+        assert_eq!(result.unwrap_err(), Interrupted::OutOfBudget);
+        let result = solver.solve_cancellable(&AtomicBool::new(true), usize::MAX);
+        assert_eq!(result.unwrap_err(), Interrupted::Cancelled);
+    }
+
+    #[test]
+    fn finds_the_centre_of_square_boards() {
+        let game = Game::new(&variant("english")).unwrap();
+        assert_eq!(centre(game.shape()), Some((3, 3)));
+        let game = Game::new(&variant("triangular")).unwrap();
+        assert_eq!(centre(game.shape()), None);
+    }
+}
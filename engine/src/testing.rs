@@ -0,0 +1,27 @@
+use crate::file::ParseError;
+use crate::game::Game;
+use crate::level::Level;
+use crate::variants;
+use std::fmt::Debug;
+use std::str::FromStr;
+
+// Fixtures shared by the tests of the engine modules
+
+// Built-in variant by key
+pub fn variant(key: &str) -> Level {
+    let index = variants::position(key).expect("unknown variant");
+    variants::VARIANTS[index].level()
+}
+
+// Game on a board given in the level file format
+pub fn game(source: &str) -> Game {
+    Game::new(&source.parse().expect("invalid test level")).expect("test level too large")
+}
+
+// Error from parsing a file that must not parse
+pub fn parse_error<T>(source: &str) -> ParseError
+where
+    T: FromStr<Err = ParseError> + Debug,
+{
+    source.parse::<T>().unwrap_err()
+}
@@ -0,0 +1,102 @@
+use std::fmt;
+use std::str::FromStr;
+
+// Square boards connect each slot to its orthogonal neighbours.
+//
+// Triangular boards are stored with every row left-aligned, so the slot (x, y) has the slots
+// (x, y - 1) and (x - 1, y - 1) above it. Rendering shifts each row by half a tile to form the
+// triangle, which turns the offsets (1, 1) and (-1, -1) into the two remaining hex directions.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Topology {
+    #[default]
+    Square,
+    Triangular,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Direction {
+    pub fn is_diagonal(&self) -> bool {
+        matches!(
+            self,
+            Direction::UpLeft | Direction::UpRight | Direction::DownLeft | Direction::DownRight
+        )
+    }
+}
+
+const SQUARE_DIRECTIONS: [(isize, isize); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+const HEX_DIRECTIONS: [(isize, isize); 6] = [(0, -1), (0, 1), (-1, 0), (1, 0), (-1, -1), (1, 1)];
+
+impl Topology {
+    // Unit offsets along which a peg may jump
+    pub fn directions(&self) -> &'static [(isize, isize)] {
+        match self {
+            Topology::Square => &SQUARE_DIRECTIONS,
+            Topology::Triangular => &HEX_DIRECTIONS,
+        }
+    }
+
+    // Offsets to try, in order, when moving the cursor in the given direction
+    pub fn cursor_offsets(&self, direction: Direction) -> &'static [(isize, isize)] {
+        match (self, direction) {
+            (_, Direction::Left) => &[(-1, 0)],
+            (_, Direction::Right) => &[(1, 0)],
+            (Topology::Square, Direction::Up) => &[(0, -1)],
+            (Topology::Square, Direction::Down) => &[(0, 1)],
+            (Topology::Triangular, Direction::Up) => &[(-1, -1), (0, -1)],
+            (Topology::Triangular, Direction::Down) => &[(1, 1), (0, 1)],
+            (_, Direction::UpLeft) => &[(-1, -1)],
+            (Topology::Square, Direction::UpRight) => &[(1, -1)],
+            (Topology::Square, Direction::DownLeft) => &[(-1, 1)],
+            (Topology::Triangular, Direction::UpRight) => &[(0, -1)],
+            (Topology::Triangular, Direction::DownLeft) => &[(0, 1)],
+            (_, Direction::DownRight) => &[(1, 1)],
+        }
+    }
+
+    // Horizontal shift of a row, in tiles
+    pub fn row_offset(&self, y: usize, height: usize) -> f32 {
+        match self {
+            Topology::Square => 0.0,
+            Topology::Triangular => (height - 1 - y) as f32 / 2.0,
+        }
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        match self {
+            Topology::Square => true,
+            Topology::Triangular => x <= y,
+        }
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Topology::Square => write!(f, "square"),
+            Topology::Triangular => write!(f, "triangular"),
+        }
+    }
+}
+
+impl FromStr for Topology {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "square" => Ok(Topology::Square),
+            "triangular" => Ok(Topology::Triangular),
+            _ => Err(()),
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use crate::level::{self, Level, LevelError};
+use std::fmt;
+use std::path::Path;
+
+// Built-in catalogue of the classic board variants.
+// Each variant is described in the level file format and may be selected by any of its keys.
+pub struct Variant {
+    pub keys: &'static [&'static str],
+    source: &'static str,
+}
+
+impl Variant {
+    pub fn key(&self) -> &'static str {
+        self.keys[0]
+    }
+
+    pub fn level(&self) -> Level {
+        self.source
+            .parse()
+            .unwrap_or_else(|e| panic!("invalid built-in variant `{}`: {}", self.key(), e))
+    }
+}
+
+pub const DEFAULT_VARIANT: &str = "english";
+
+pub const VARIANTS: &[Variant] = &[
+    // 33 holes, 32 pegs
+    Variant {
+        keys: &["english"],
+        source: "\
+name: English
+author: Traditional
+goal: centre
+
+..XXX..
+..XXX..
+XXXXXXX
+XXXOXXX
+XXXXXXX
+..XXX..
+..XXX..
+",
+    },
+    // 37 holes, 36 pegs. The centre vacancy cannot be solved on this board.
+    Variant {
+        keys: &["french", "european"],
+        source: "\
+name: French
+author: Traditional
+goal: single
+
+..XXX..
+.XXOXX.
+XXXXXXX
+XXXXXXX
+XXXXXXX
+.XXXXX.
+..XXX..
+",
+    },
+    // 45 holes, 44 pegs
+    Variant {
+        keys: &["german", "wiegleb"],
+        source: "\
+name: German
+author: J. C. Wiegleb
+goal: centre
+
+...XXX...
+...XXX...
+...XXX...
+XXXXXXXXX
+XXXXOXXXX
+XXXXXXXXX
+...XXX...
+...XXX...
+...XXX...
+",
+    },
+    // 41 holes, 40 pegs. The centre vacancy cannot be solved on this board.
+    Variant {
+        keys: &["diamond"],
+        source: "\
+name: Diamond
+author: Traditional
+goal: single
+
+....X....
+...XXX...
+..XXXXX..
+.XXXOXXX.
+XXXXXXXXX
+.XXXXXXX.
+..XXXXX..
+...XXX...
+....X....
+",
+    },
+    // 39 holes, 38 pegs
+    Variant {
+        keys: &["asymmetrical", "3-3-2-2"],
+        source: "\
+name: Asymmetrical 3-3-2-2
+author: Traditional
+goal: single
+
+...XXX..
+...XXX..
+...XXX..
+XXXXXXXX
+XXXXOXXX
+XXXXXXXX
+...XXX..
+...XXX..
+",
+    },
+    // 15 holes, 14 pegs
+    Variant {
+        keys: &["triangular", "triangular-15"],
+        source: "\
+name: Triangular
+author: Traditional
+goal: single
+topology: triangular
+
+O....
+XX...
+XXX..
+XXXX.
+XXXXX
+",
+    },
+    // 21 holes, 20 pegs
+    Variant {
+        keys: &["triangular-21"],
+        source: "\
+name: Triangular 21
+author: Traditional
+goal: single
+topology: triangular
+
+O.....
+XX....
+XXX...
+XXXX..
+XXXXX.
+XXXXXX
+",
+    },
+];
+
+// Built-in variants followed by the levels in the directory. Level files that cannot be loaded are
+// left out, and their errors returned along with any error reading the directory.
+pub fn catalogue<P: AsRef<Path>>(dir: P) -> (Vec<Level>, Vec<LevelError>) {
+    let mut levels: Vec<Level> = VARIANTS.iter().map(|v| v.level()).collect();
+    match level::load_levels(dir) {
+        Ok((custom, errors)) => {
+            levels.extend(custom);
+            (levels, errors)
+        }
+        Err(e) => (levels, vec![e]),
+    }
+}
+
+pub fn position(key: &str) -> Option<usize> {
+    VARIANTS
+        .iter()
+        .position(|v| v.keys.iter().any(|k| k.eq_ignore_ascii_case(key)))
+}
+
+// Looks up a built-in variant by key, falling back to the levels by name. The catalogue must start
+// with the built-in variants.
+pub fn find(levels: &[Level], name: &str) -> Result<usize, UnknownVariant> {
+    if let Some(index) = position(name) {
+        return Ok(index);
+    }
+
+    levels
+        .iter()
+        .position(|l| l.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| UnknownVariant(name.to_string()))
+}
+
+// A name that is neither a built-in variant nor a level
+#[derive(Debug)]
+pub struct UnknownVariant(pub String);
+
+impl fmt::Display for UnknownVariant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let keys: Vec<&str> = VARIANTS.iter().map(|v| v.key()).collect();
+        write!(
+            f,
+            "unknown variant `{}` (built-in variants: {})",
+            self.0,
+            keys.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for UnknownVariant {}
@@ -0,0 +1,148 @@
+use pegsol_engine::level::Level;
+use pegsol_engine::notation;
+use pegsol_engine::options::{BoardError, BoardOptions};
+use std::fmt;
+
+// Command line arguments both front-ends take. Options take their value either as the next
+// argument or after an equals sign, e.g. `--variant french` or `--variant=french`.
+
+// Takes the option if it is a board option, calling `value` for its value if it needs one. Returns
+// whether it was a board option.
+pub fn parse_board_option<F>(
+    board: &mut BoardOptions,
+    option: &str,
+    value: F,
+) -> Result<bool, UsageError>
+where
+    F: FnOnce() -> Result<String, UsageError>,
+{
+    match option {
+        "--variant" => board.variant = Some(value()?),
+        "--start" => {
+            let slot = value()?;
+            let start = notation::parse_slot(&slot)
+                .ok_or_else(|| UsageError(format!("invalid slot `{}`", slot)))?;
+            board.start = Some(start);
+        }
+        "--diagonal" => board.diagonal = true,
+        "--chains" => board.chains = true,
+        _ => return Ok(false),
+    }
+
+    Ok(true)
+}
+
+// Applies the options to a level file given on the command line, which is the board to play
+pub fn apply_to_level(board: &BoardOptions, level: &mut Level) -> Result<(), UsageError> {
+    if board.variant.is_some() {
+        return Err(UsageError(
+            "a level file cannot be combined with `--variant`".to_string(),
+        ));
+    }
+
+    Ok(board.apply_to_level(level)?)
+}
+
+// A game record brings its own board, rules and start, so none of the options apply to it
+pub fn check_record(board: &BoardOptions) -> Result<(), UsageError> {
+    let option = if board.variant.is_some() {
+        "--variant"
+    } else if board.start.is_some() {
+        "--start"
+    } else if board.diagonal {
+        "--diagonal"
+    } else if board.chains {
+        "--chains"
+    } else {
+        return Ok(());
+    };
+
+    Err(UsageError(format!(
+        "a game record cannot be combined with `{}`",
+        option
+    )))
+}
+
+// Splits `--option=value` into the option and its value
+pub fn split_option(arg: &str) -> (&str, Option<&str>) {
+    match arg.find('=') {
+        Some(eq) => (&arg[..eq], Some(&arg[eq + 1..])),
+        None => (arg, None),
+    }
+}
+
+// Value of the option, given after an equals sign or else as the next argument
+pub fn option_value(
+    option: &str,
+    inline_value: Option<&str>,
+    args: &mut impl Iterator<Item = String>,
+) -> Result<String, UsageError> {
+    inline_value
+        .map(str::to_string)
+        .or_else(|| args.next())
+        .ok_or_else(|| UsageError(format!("option `{}` needs a value", option)))
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UsageError(pub String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for UsageError {}
+
+// A board the options cannot set up is a mistake on the command line
+impl From<BoardError> for UsageError {
+    fn from(error: BoardError) -> Self {
+        UsageError(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pegsol_engine::variants;
+
+    fn options(args: &[&str]) -> Result<BoardOptions, UsageError> {
+        let mut board = BoardOptions::default();
+        let mut args = args.iter().map(|a| a.to_string());
+        while let Some(arg) = args.next() {
+            let (option, inline_value) = split_option(&arg);
+            let value = || option_value(option, inline_value, &mut args);
+            if !parse_board_option(&mut board, option, value)? {
+                return Err(UsageError(format!("unknown option `{}`", arg)));
+            }
+        }
+
+        Ok(board)
+    }
+
+    #[test]
+    fn options_take_values_inline_or_next() {
+        let board = options(&["--variant=french", "--start", "d2", "--chains"]).unwrap();
+        assert_eq!(board.variant.as_deref(), Some("french"));
+        assert_eq!(board.start, Some((3, 1)));
+        assert!(board.chains && !board.diagonal);
+
+        assert!(options(&["--start"]).is_err());
+        assert!(options(&["--start=4d"]).is_err());
+    }
+
+    #[test]
+    fn rejects_options_that_cannot_apply() {
+        let mut level = variants::VARIANTS[0].level();
+        let board = options(&["--variant", "french"]).unwrap();
+        assert!(apply_to_level(&board, &mut level).is_err());
+        assert!(check_record(&board).is_err());
+
+        for args in [&["--start", "d4"][..], &["--diagonal"], &["--chains"]].iter() {
+            let board = options(args).unwrap();
+            assert!(apply_to_level(&board, &mut level).is_ok());
+            assert!(check_record(&board).is_err(), "{:?}", args);
+        }
+        assert!(check_record(&BoardOptions::default()).is_ok());
+    }
+}
@@ -0,0 +1,60 @@
+use pegsol_engine::game::Game;
+use pegsol_engine::level::Level;
+use pegsol_engine::options::BoardChoice;
+use pegsol_engine::variants;
+
+// Level directory of both front-ends, relative to the repository root
+pub const LEVELS_DIR: &str = "./assets/levels";
+
+// Built-in variants followed by the level files, skipping the files that cannot be read
+pub fn load_catalogue() -> Vec<Level> {
+    let (levels, errors) = variants::catalogue(LEVELS_DIR);
+    // errors name the file already
+    for e in errors {
+        eprintln!("skipping {}", e);
+    }
+
+    levels
+}
+
+// Boards to play in turn, and the one being played
+pub struct Catalogue {
+    levels: Vec<Level>,
+    current: usize,
+    // Slot left empty at the start of the current level, instead of its usual one
+    start: Option<(usize, usize)>,
+}
+
+impl Catalogue {
+    pub fn new(levels: Vec<Level>, (current, start): BoardChoice) -> Self {
+        Self {
+            levels,
+            current,
+            start,
+        }
+    }
+
+    pub fn level(&self) -> &Level {
+        &self.levels[self.current]
+    }
+
+    pub fn start(&self) -> Option<(usize, usize)> {
+        self.start
+    }
+
+    // Title of the window or screen the board is played in
+    pub fn title(&self) -> String {
+        format!("Peg Solitaire - {}", self.level().title())
+    }
+
+    // Sets up a game on the current board, from its start
+    pub fn start_game(&self) -> Game {
+        self.level().start_game(self.start)
+    }
+
+    // Moves on to the next board, which starts the usual way
+    pub fn next(&mut self) {
+        self.current = (self.current + 1) % self.levels.len();
+        self.start = None;
+    }
+}
@@ -0,0 +1,18 @@
+// What came of the hint the player asked for in the current position
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum HintState {
+    #[default]
+    NotAsked,
+    Searching,
+    // No solution from the position
+    Unsolvable,
+    // The search gave up before finding a jump or proving there is none
+    GaveUp,
+}
+
+impl HintState {
+    // Asking again while the search runs, or after it gave up, would only start it over
+    pub fn can_ask(self) -> bool {
+        !matches!(self, HintState::Searching | HintState::GaveUp)
+    }
+}
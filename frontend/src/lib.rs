@@ -0,0 +1,6 @@
+// Parts shared by the window and terminal front-ends that are not rules of the game: reading the
+// board options from the command line, the boards to play in turn and the state of the hint.
+
+pub mod args;
+pub mod catalogue;
+pub mod hint;
@@ -0,0 +1,341 @@
+use crate::render::RenderOptions;
+use crate::resources::AnimationSpeed;
+use pegsol_engine::options::BoardOptions;
+use pegsol_engine::solver::SEARCH_BUDGET;
+use pegsol_frontend::args::{option_value, parse_board_option, split_option, UsageError};
+use std::path::PathBuf;
+
+// Command line of the game. Options take their value either as the next argument or after an
+// equals sign, e.g. `--variant french` or `--variant=french`. The board options are shared with
+// the terminal front-end.
+
+pub const USAGE: &str = "\
+Peg Solitaire
+
+Usage:
+  pegsol [play] [BOARD OPTIONS] [--load <RECORD>] [WINDOW OPTIONS]
+  pegsol solve [<FILE>] [BOARD OPTIONS] [--budget <POSITIONS>]
+  pegsol render [<FILE>] [BOARD OPTIONS] --output <PNG> [--last-move] [--labels]
+  pegsol animate <RECORD> --output <GIF>
+  pegsol validate <LEVEL>...
+  pegsol replay <RECORD> [WINDOW OPTIONS]
+  pegsol help
+
+Commands:
+  play       Play in a window (the default)
+  solve      Print a solution as a game record. FILE is a level file or a game
+             record, whose remaining moves are solved.
+  render     Draw the board to a PNG image. FILE is a level file or a game
+             record, drawn as it ends.
+  animate    Turn a game record into an animated GIF of its jumps
+  validate   Check level files for errors and for boards that cannot be won
+  replay     Watch a game record
+  help       Print this message
+
+Board options:
+  --variant <NAME>   Built-in variant or level name [default: english]
+  --start <SLOT>     Slot left empty at the start, e.g. d4
+  --diagonal         Allow diagonal jumps on square boards
+  --chains           Count jumps chained by the same peg as a single move
+
+  A game record brings its own board, so it takes no board options. A level
+  file takes all of them but `--variant`.
+
+Play options:
+  --load <RECORD>    Continue a saved game
+
+Solve options:
+  --budget <POSITIONS>  Positions to search before giving up
+                        [default: 10000000]
+
+Window options, for play and replay:
+  --animation <SPEED>  Speed of jumps: off, slow, normal or fast
+                       [default: normal]
+
+Render and animate options:
+  --output <FILE>    Image file to write
+  --last-move        Highlight the last jump
+  --labels           Name every slot
+
+Exit status:
+  0  success
+  1  the command failed, e.g. a level is invalid or there is no solution
+  2  the command line is invalid
+";
+
+// Exit status of a failed command
+pub const EXIT_FAILURE: i32 = 1;
+// Exit status of an invalid command line
+pub const EXIT_USAGE: i32 = 2;
+
+#[derive(Debug)]
+pub enum Command {
+    Help,
+    Play {
+        board: BoardOptions,
+        load: Option<PathBuf>,
+        animation: AnimationSpeed,
+    },
+    Solve {
+        board: BoardOptions,
+        file: Option<PathBuf>,
+        budget: usize,
+    },
+    Render {
+        board: BoardOptions,
+        file: Option<PathBuf>,
+        output: PathBuf,
+        options: RenderOptions,
+    },
+    Animate {
+        record: PathBuf,
+        output: PathBuf,
+    },
+    Validate {
+        levels: Vec<PathBuf>,
+    },
+    Replay {
+        record: PathBuf,
+        animation: AnimationSpeed,
+    },
+}
+
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Command, UsageError> {
+    let mut args = args.into_iter().peekable();
+
+    // `play` may be left out
+    let name = match args.peek().map(String::as_str) {
+        Some("play") | Some("solve") | Some("render") | Some("animate") | Some("validate")
+        | Some("replay") | Some("help") => args.next().unwrap_or_default(),
+        _ => "play".to_string(),
+    };
+
+    let mut board = BoardOptions::default();
+    let mut load = None;
+    let mut output = None;
+    let mut animation = AnimationSpeed::default();
+    let mut budget = SEARCH_BUDGET;
+    let mut render = RenderOptions::default();
+    let mut paths = Vec::new();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            paths.push(PathBuf::from(arg));
+            continue;
+        }
+
+        let (option, inline_value) = split_option(&arg);
+        let mut value = || option_value(option, inline_value, &mut args);
+
+        let takes_board = name == "play" || name == "solve" || name == "render";
+        if takes_board && parse_board_option(&mut board, option, &mut value)? {
+            continue;
+        }
+
+        match option {
+            "--help" => return Ok(Command::Help),
+            "--load" if name == "play" => load = Some(PathBuf::from(value()?)),
+            "--animation" if name == "play" || name == "replay" => {
+                let speed = value()?;
+                animation = speed
+                    .parse()
+                    .map_err(|_| UsageError(format!("invalid animation speed `{}`", speed)))?;
+            }
+            "--budget" if name == "solve" => {
+                let positions = value()?;
+                budget = positions
+                    .parse()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| UsageError(format!("invalid budget `{}`", positions)))?;
+            }
+            "--output" if name == "render" || name == "animate" => {
+                output = Some(PathBuf::from(value()?))
+            }
+            "--last-move" if name == "render" => render.last_move = true,
+            "--labels" if name == "render" => render.labels = true,
+            _ => {
+                return Err(UsageError(format!(
+                    "unknown option `{}` for `{}`",
+                    option, name
+                )))
+            }
+        }
+    }
+
+    match name.as_str() {
+        "help" | "play" if !paths.is_empty() => Err(UsageError(format!(
+            "unexpected argument `{}` for `{}`",
+            paths[0].display(),
+            name
+        ))),
+        "help" => Ok(Command::Help),
+        "play" => Ok(Command::Play {
+            board,
+            load,
+            animation,
+        }),
+        "solve" => Ok(Command::Solve {
+            board,
+            file: at_most_one(paths, &name)?,
+            budget,
+        }),
+        "render" | "animate" if output.is_none() => {
+            Err(UsageError(format!("`{}` needs an `--output` file", name)))
+        }
+        "render" => Ok(Command::Render {
+            board,
+            file: at_most_one(paths, &name)?,
+            output: output.unwrap_or_default(),
+            options: render,
+        }),
+        "animate" => {
+            let record = at_most_one(paths, &name)?
+                .ok_or_else(|| UsageError("`animate` needs a game record".to_string()))?;
+            Ok(Command::Animate {
+                record,
+                output: output.unwrap_or_default(),
+            })
+        }
+        "validate" if paths.is_empty() => {
+            Err(UsageError("`validate` needs a level file".to_string()))
+        }
+        "validate" => Ok(Command::Validate { levels: paths }),
+        _ => {
+            let record = at_most_one(paths, &name)?
+                .ok_or_else(|| UsageError("`replay` needs a game record".to_string()))?;
+            Ok(Command::Replay { record, animation })
+        }
+    }
+}
+
+fn at_most_one(mut paths: Vec<PathBuf>, command: &str) -> Result<Option<PathBuf>, UsageError> {
+    if paths.len() > 1 {
+        return Err(UsageError(format!(
+            "unexpected argument `{}` for `{}`",
+            paths[1].display(),
+            command
+        )));
+    }
+
+    Ok(paths.pop())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Command, UsageError> {
+        parse(args.iter().map(|a| a.to_string()))
+    }
+
+    fn error(args: &[&str]) -> String {
+        parse_args(args)
+            .expect_err("command line should be rejected")
+            .0
+    }
+
+    #[test]
+    fn play_may_be_left_out() {
+        assert!(matches!(
+            parse_args(&[]),
+            Ok(Command::Play { load: None, .. })
+        ));
+
+        match parse_args(&["--variant", "french", "--animation", "fast"]) {
+            Ok(Command::Play {
+                board, animation, ..
+            }) => {
+                assert_eq!(board.variant.as_deref(), Some("french"));
+                assert_eq!(animation, AnimationSpeed::Fast);
+            }
+            other => panic!("expected `play`, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn options_take_values_inline_or_next() {
+        for args in [
+            &["solve", "--budget=500"][..],
+            &["solve", "--budget", "500"],
+        ]
+        .iter()
+        {
+            match parse_args(args) {
+                Ok(Command::Solve { budget, file, .. }) => {
+                    assert_eq!(budget, 500);
+                    assert_eq!(file, None);
+                }
+                other => panic!("expected `solve`, got {:?}", other),
+            }
+        }
+
+        match parse_args(&["render", "--output=board.png", "--start", "d2", "--labels"]) {
+            Ok(Command::Render {
+                board,
+                output,
+                options,
+                ..
+            }) => {
+                assert_eq!(output, PathBuf::from("board.png"));
+                assert_eq!(board.start, Some((3, 1)));
+                assert!(options.labels && !options.last_move);
+            }
+            other => panic!("expected `render`, got {:?}", other),
+        }
+
+        assert_eq!(
+            error(&["solve", "--budget"]),
+            "option `--budget` needs a value"
+        );
+        assert_eq!(error(&["solve", "--budget=0"]), "invalid budget `0`");
+    }
+
+    #[test]
+    fn rejects_options_the_command_does_not_take() {
+        assert_eq!(
+            error(&["replay", "game.rec", "--variant", "french"]),
+            "unknown option `--variant` for `replay`"
+        );
+        assert_eq!(
+            error(&["render", "--output", "board.png", "--budget", "5"]),
+            "unknown option `--budget` for `render`"
+        );
+        assert_eq!(
+            error(&["validate", "board.lvl", "--load=game.rec"]),
+            "unknown option `--load` for `validate`"
+        );
+        assert_eq!(
+            error(&["--output", "board.png"]),
+            "unknown option `--output` for `play`"
+        );
+    }
+
+    #[test]
+    fn render_and_animate_need_an_output() {
+        assert_eq!(error(&["render"]), "`render` needs an `--output` file");
+        assert_eq!(
+            error(&["animate", "game.rec"]),
+            "`animate` needs an `--output` file"
+        );
+    }
+
+    #[test]
+    fn rejects_more_than_one_path() {
+        assert_eq!(
+            error(&["solve", "a.lvl", "b.lvl"]),
+            "unexpected argument `b.lvl` for `solve`"
+        );
+        assert_eq!(
+            error(&["animate", "a.rec", "b.rec", "--output", "game.gif"]),
+            "unexpected argument `b.rec` for `animate`"
+        );
+        assert_eq!(
+            error(&["play", "a.lvl"]),
+            "unexpected argument `a.lvl` for `play`"
+        );
+        assert!(matches!(
+            parse_args(&["validate", "a.lvl", "b.lvl"]),
+            Ok(Command::Validate { levels }) if levels.len() == 2
+        ));
+    }
+}
@@ -12,6 +12,16 @@ pub struct Highlighted;
 #[storage(NullStorage)]
 pub struct Occupied;
 
+// Empty slot the selected peg can jump to
+#[derive(Component, Default)]
+#[storage(NullStorage)]
+pub struct Destination;
+
+// Peg that has a jump, marked when movable pegs are shown
+#[derive(Component, Default)]
+#[storage(NullStorage)]
+pub struct Movable;
+
 #[derive(Debug, Component, Clone, Copy, Default)]
 #[storage(VecStorage)]
 pub struct Slot {
@@ -23,5 +33,7 @@ pub fn register_components(world: &mut World) {
     world.register::<Selected>();
     world.register::<Highlighted>();
     world.register::<Occupied>();
+    world.register::<Destination>();
+    world.register::<Movable>();
     world.register::<Slot>();
 }
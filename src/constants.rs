@@ -1,14 +1,10 @@
-pub const BOARD_LEN: usize = 7;
+pub const WINDOW_WIDTH: f32 = 700.0;
+pub const WINDOW_HEIGHT: f32 = 800.0;
 pub const PADDING_TOP: f32 = 110.0;
+pub const PADDING_BOTTOM: f32 = 10.0;
 pub const PADDING_LEFT: f32 = 10.0;
-pub const PEG_COUNT: u8 = 32;
+pub const PADDING_RIGHT: f32 = 10.0;
+// Size of the slot sprites. Tiles are scaled down from this when the board doesn't fit the window.
 pub const TILE_SIZE: f32 = 97.0;
-pub const LAYOUT: [[char; BOARD_LEN]; BOARD_LEN] = [
-    ['.', '.', 'X', 'X', 'X', '.', '.'],
-    ['.', '.', 'X', 'X', 'X', '.', '.'],
-    ['X', 'X', 'X', 'X', 'X', 'X', 'X'],
-    ['X', 'X', 'X', 'O', 'X', 'X', 'X'],
-    ['X', 'X', 'X', 'X', 'X', 'X', 'X'],
-    ['.', '.', 'X', 'X', 'X', '.', '.'],
-    ['.', '.', 'X', 'X', 'X', '.', '.'],
-];
+pub const ASSETS_DIR: &str = "./assets";
+pub const RECORDS_DIR: &str = "./records";
@@ -1,78 +1,67 @@
 use crate::components::*;
-use crate::constants::{BOARD_LEN, LAYOUT};
+use crate::resources::GameState;
+use pegsol_engine::position::{Position, Shape};
+use pegsol_engine::topology::Topology;
 use specs::{world::Builder, world::Entity, World, WorldExt};
 
-pub struct Board([[Option<Entity>; BOARD_LEN]; BOARD_LEN]);
-
-impl Default for Board {
-    fn default() -> Self {
-        Self([[None; BOARD_LEN]; BOARD_LEN])
-    }
+// Entities of the slots, by position on the board
+pub struct Board {
+    width: usize,
+    height: usize,
+    topology: Topology,
+    slots: Vec<Option<Entity>>,
 }
 
 impl Board {
-    pub fn add_entity(&mut self, x: usize, y: usize, entity: Entity) {
-        self.0[y][x] = Some(entity);
-    }
-
-    pub fn entity_at(&self, x: usize, y: usize) -> Option<Entity> {
-        if x >= BOARD_LEN || y >= BOARD_LEN {
-            return None;
+    pub fn new(width: usize, height: usize, topology: Topology) -> Self {
+        Self {
+            width,
+            height,
+            topology,
+            slots: vec![None; width * height],
         }
-
-        self.0[y][x]
     }
 
-    pub fn entity_above(&self, x: usize, y: usize) -> Option<Entity> {
-        if y == 0 {
-            return None;
-        }
-
-        self.0[y - 1][x]
+    pub fn topology(&self) -> Topology {
+        self.topology
     }
 
-    pub fn entity_below(&self, x: usize, y: usize) -> Option<Entity> {
-        if y == BOARD_LEN - 1 {
-            return None;
-        }
-
-        self.0[y + 1][x]
+    pub fn width(&self) -> usize {
+        self.width
     }
 
-    pub fn entity_to_left(&self, x: usize, y: usize) -> Option<Entity> {
-        if x == 0 {
-            return None;
-        }
+    pub fn height(&self) -> usize {
+        self.height
+    }
 
-        self.0[y][x - 1]
+    pub fn add_entity(&mut self, x: usize, y: usize, entity: Entity) {
+        self.slots[y * self.width + x] = Some(entity);
     }
 
-    pub fn entity_to_right(&self, x: usize, y: usize) -> Option<Entity> {
-        if x == BOARD_LEN - 1 {
+    pub fn entity_at(&self, x: usize, y: usize) -> Option<Entity> {
+        if x >= self.width || y >= self.height {
             return None;
         }
 
-        self.0[y][x + 1]
+        self.slots[y * self.width + x]
     }
 }
 
-pub fn create_board(world: &mut World) -> Board {
-    let mut board = Board::default();
-
-    for (y, row) in LAYOUT.iter().enumerate() {
-        for (x, col) in row.iter().enumerate() {
-            match col {
-                'X' => {
-                    let entity = create_occupied_slot(world, x, y);
-                    board.add_entity(x, y, entity);
-                }
-                'O' => {
-                    let entity = create_empty_slot(world, x, y);
-                    board.add_entity(x, y, entity);
-                }
-                _ => (),
-            }
-        }
+// Creates an entity for every slot, occupied according to the position
+pub fn create_board(world: &mut World, shape: &Shape, position: &Position) -> Board {
+    let mut board = Board::new(shape.width(), shape.height(), shape.topology());
+    let mut highlight = true;
+
+    for (hole, (x, y)) in shape.holes().iter().copied().enumerate() {
+        let entity = if position.is_occupied(hole) {
+            create_occupied_slot(world, x, y)
+        } else {
+            // the cursor starts on the first empty slot
+            let entity = create_empty_slot(world, x, y, highlight);
+            highlight = false;
+            entity
+        };
+        board.add_entity(x, y, entity);
     }
 
     board
@@ -86,10 +75,35 @@ fn create_occupied_slot(world: &mut World, x: usize, y: usize) -> Entity {
         .build()
 }
 
-fn create_empty_slot(world: &mut World, x: usize, y: usize) -> Entity {
-    world
-        .create_entity()
-        .with(Slot { x, y })
-        .with(Highlighted)
-        .build()
+fn create_empty_slot(world: &mut World, x: usize, y: usize, highlight: bool) -> Entity {
+    let builder = world.create_entity().with(Slot { x, y });
+    if highlight {
+        builder.with(Highlighted).build()
+    } else {
+        builder.build()
+    }
+}
+
+// Brings every slot in line with the position of the game, after it was changed without going
+// through the `GamePlaySystem`
+pub fn sync_board(world: &World) {
+    let game_state = world.read_resource::<GameState>();
+    let mut occupied = world.write_storage::<Occupied>();
+    let shape = game_state.game.shape();
+    let position = game_state.game.position();
+
+    for (hole, (x, y)) in shape.holes().iter().enumerate() {
+        let entity = game_state
+            .board
+            .entity_at(*x, *y)
+            .expect("slot entity not found");
+
+        if position.is_occupied(hole) {
+            occupied
+                .insert(entity, Occupied)
+                .expect("failed to mark entity as occupied");
+        } else {
+            occupied.remove(entity);
+        }
+    }
 }
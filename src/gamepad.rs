@@ -0,0 +1,69 @@
+use crate::resources::{InputEvent, ReplayAction};
+use ggez::event::{Axis, Button, GamepadId};
+use std::collections::HashMap;
+
+// Controller input, mapped onto the same events as the keyboard. Controllers plugged in while the
+// game runs are picked up as soon as they send their first event.
+
+// How far a stick has to be pushed to move the cursor, and how close to the centre it has to come
+// back before it can move it again
+const PUSH: f32 = 0.6;
+const RELEASE: f32 = 0.3;
+
+pub fn input_event(button: Button) -> Option<InputEvent> {
+    match button {
+        Button::DPadUp => Some(InputEvent::Up),
+        Button::DPadDown => Some(InputEvent::Down),
+        Button::DPadLeft => Some(InputEvent::Left),
+        Button::DPadRight => Some(InputEvent::Right),
+        Button::South => Some(InputEvent::Enter),
+        Button::East => Some(InputEvent::Deselect),
+        Button::LeftTrigger => Some(InputEvent::Undo),
+        Button::RightTrigger => Some(InputEvent::Redo),
+        _ => None,
+    }
+}
+
+pub fn replay_action(button: Button) -> Option<ReplayAction> {
+    match button {
+        Button::DPadRight => Some(ReplayAction::Forward),
+        Button::DPadLeft => Some(ReplayAction::Back),
+        Button::South => Some(ReplayAction::TogglePlay),
+        Button::DPadUp => Some(ReplayAction::Faster),
+        Button::DPadDown => Some(ReplayAction::Slower),
+        _ => None,
+    }
+}
+
+// Left sticks of the controllers, which move the cursor one slot each time they are pushed
+#[derive(Default)]
+pub struct Sticks {
+    pushed: HashMap<(GamepadId, Axis), bool>,
+}
+
+impl Sticks {
+    // Returns the cursor step if the stick has just been pushed along the axis
+    pub fn step(&mut self, id: GamepadId, axis: Axis, value: f32) -> Option<InputEvent> {
+        if axis != Axis::LeftStickX && axis != Axis::LeftStickY {
+            return None;
+        }
+
+        let pushed = self.pushed.entry((id, axis)).or_insert(false);
+        if *pushed {
+            *pushed = value.abs() >= RELEASE;
+            return None;
+        }
+        if value.abs() < PUSH {
+            return None;
+        }
+
+        *pushed = true;
+        // the Y axis points up
+        match (axis, value > 0.0) {
+            (Axis::LeftStickX, true) => Some(InputEvent::Right),
+            (Axis::LeftStickX, false) => Some(InputEvent::Left),
+            (_, true) => Some(InputEvent::Up),
+            (_, false) => Some(InputEvent::Down),
+        }
+    }
+}
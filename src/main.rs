@@ -1,58 +1,426 @@
+use crate::cli::Command;
 use crate::resources::{
-    AssetStore, GameEventQueue, GameState, InputEvent, InputEventQueue, MoveQueue,
+    Animation, AnimationSpeed, AssetStore, Drag, GameEventQueue, GameState, Geometry, HintRequest,
+    HistoryQueue, InputEvent, InputEventQueue, MoveHints, MoveQueue, Replay, ReplayAction,
+    ReplayQueue, Solvability,
 };
-use ggez;
-use ggez::event::{KeyCode, KeyMods, MouseButton};
-use ggez::{conf, event, Context, GameResult};
+use ggez::event::{Axis, Button, GamepadId, KeyCode, KeyMods, MouseButton};
+use ggez::{conf, event, graphics, timer, Context, GameResult};
+use pegsol_engine::level::{self, Level};
+use pegsol_engine::options::{BoardChoice, BoardError, BoardOptions};
+use pegsol_engine::record::{self, GameRecord};
+use pegsol_engine::solver::{Interrupted, Solver};
+use pegsol_engine::{lint, notation, variants};
+use pegsol_frontend::args::{self, UsageError};
+use pegsol_frontend::catalogue::{load_catalogue, Catalogue};
+use pegsol_frontend::hint::HintState;
 use specs::{RunNow, World, WorldExt};
-use std::path;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::{env, path, process};
 
+mod cli;
 mod components;
 mod constants;
 mod entities;
+mod gamepad;
+mod render;
 mod resources;
 mod systems;
 
-fn main() -> GameResult {
-    // create new World and register components and resources
-    let mut world = World::new();
-    components::register_components(&mut world);
+fn main() {
+    let command = match cli::parse(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => exit_with(e.into()),
+    };
 
-    let board = entities::create_board(&mut world);
-    world.insert(GameState::new(board));
-    world.insert(InputEventQueue::default());
-    world.insert(GameEventQueue::default());
-    world.insert(MoveQueue::default());
-    world.insert(AssetStore::default());
+    let result = match command {
+        Command::Help => {
+            print!("{}", cli::USAGE);
+            Ok(())
+        }
+        Command::Play {
+            board,
+            load,
+            animation,
+        } => play(&board, load.as_deref(), animation),
+        Command::Solve {
+            board,
+            file,
+            budget,
+        } => solve(&board, file.as_deref(), budget),
+        Command::Render {
+            board,
+            file,
+            output,
+            options,
+        } => render_png(&board, file.as_deref(), &output, options),
+        Command::Animate { record, output } => animate_gif(&record, &output),
+        Command::Validate { levels } => validate(&levels),
+        Command::Replay { record, animation } => replay(&record, animation),
+    };
+
+    if let Err(e) = result {
+        exit_with(e);
+    }
+}
+
+// Reports the error and exits with the status documented in the usage
+fn exit_with(error: Box<dyn Error>) -> ! {
+    // a board the options cannot set up is a mistake on the command line too
+    if error.is::<UsageError>() || error.is::<BoardError>() {
+        eprintln!("error: {}\n\nRun `pegsol help` for usage.", error);
+        process::exit(cli::EXIT_USAGE);
+    }
+
+    eprintln!("error: {}", error);
+    process::exit(cli::EXIT_FAILURE);
+}
+
+fn play(
+    options: &BoardOptions,
+    load: Option<&Path>,
+    animation: AnimationSpeed,
+) -> Result<(), Box<dyn Error>> {
+    if load.is_some() {
+        args::check_record(options)?;
+    }
+
+    let mut levels = load_catalogue();
+    let record = load.map(record::load_record).transpose()?;
+    let board = match &record {
+        Some(record) => saved_board(&mut levels, record)?,
+        None => options.pick_board(&mut levels)?,
+    };
+
+    let mut game = Game::new(Catalogue::new(levels, board), animation);
+    if let Some(record) = &record {
+        game.replay(record)?;
+    }
+    run(game, None)
+}
+
+fn replay(path: &Path, animation: AnimationSpeed) -> Result<(), Box<dyn Error>> {
+    let mut levels = load_catalogue();
+    let record = record::load_record(path)?;
+    let board = saved_board(&mut levels, &record)?;
+
+    let mut game = Game::new(Catalogue::new(levels, board), animation);
+    game.replay(&record)?;
+    run(game, Some(&record))
+}
+
+// Prints a solution from the start of the board, or from the end of a game record
+fn solve(options: &BoardOptions, file: Option<&Path>, budget: usize) -> Result<(), Box<dyn Error>> {
+    let (level, start, game) = load_position(options, file)?;
+
+    let solver = Solver::from_game(&game).ok_or("the goal is not on the board")?;
+    if let Some(reason) = solver.explain() {
+        return Err(format!("no solution from this position: {}", reason).into());
+    }
+    let solution = match solver.solve_cancellable(&AtomicBool::new(false), budget) {
+        Ok(solution) => solution.ok_or("no solution from this position")?,
+        Err(Interrupted::OutOfBudget) => {
+            return Err(format!(
+                "gave up after searching {} positions (see `--budget`)",
+                budget
+            )
+            .into())
+        }
+        Err(Interrupted::Cancelled) => unreachable!("the search is never cancelled"),
+    };
+
+    let mut record = GameRecord::from_game(&game, &level, start);
+    record.moves.extend(solution.moves());
+    record.result = "solved".to_string();
+    print!("{}", record);
+    Ok(())
+}
+
+// Draws the board, as it starts or as a game record ends, to an image file
+fn render_png(
+    board: &BoardOptions,
+    file: Option<&Path>,
+    output: &Path,
+    options: render::RenderOptions,
+) -> Result<(), Box<dyn Error>> {
+    let (_, _, game) = load_position(board, file)?;
+    let renderer = render::Renderer::load(constants::ASSETS_DIR)?;
+    render::save_png(&renderer.render(&game, options), output)?;
+    Ok(())
+}
+
+// Draws the jumps of a game record, one after another, to an animated image file
+fn animate_gif(path: &Path, output: &Path) -> Result<(), Box<dyn Error>> {
+    let mut levels = load_catalogue();
+    let record = record::load_record(path)?;
+    let (current, start) = saved_board(&mut levels, &record)?;
+
+    let mut game = levels[current].start_game(start);
+    record.replay(&mut game)?;
+
+    let renderer = render::Renderer::load(constants::ASSETS_DIR)?;
+    render::save_gif(&renderer.animate(game), output)?;
+    Ok(())
+}
+
+// Level and start slot a game was set up from, along with the game
+type LoadedGame = (Level, Option<(usize, usize)>, pegsol_engine::game::Game);
+
+// Sets up the board given on the command line: a game record with its moves played, a level
+// file, or a variant
+fn load_position(
+    options: &BoardOptions,
+    file: Option<&Path>,
+) -> Result<LoadedGame, Box<dyn Error>> {
+    let mut levels = load_catalogue();
+    let (level, start, record) = match file {
+        Some(path)
+            if path
+                .extension()
+                .is_some_and(|e| e == record::RECORD_EXTENSION) =>
+        {
+            args::check_record(options)?;
+            let record = record::load_record(path)?;
+            let (current, start) = saved_board(&mut levels, &record)?;
+            (levels.swap_remove(current), start, Some(record))
+        }
+        Some(path) => {
+            let mut level = level::load_level(path)?;
+            args::apply_to_level(options, &mut level)?;
+            (level, options.start, None)
+        }
+        None => {
+            let (current, start) = options.pick_board(&mut levels)?;
+            (levels.swap_remove(current), start, None)
+        }
+    };
+
+    let mut game = level.start_game(start);
+    if let Some(record) = &record {
+        record.replay(&mut game)?;
+    }
+
+    Ok((level, start, game))
+}
+
+// Lints the level files, reporting every problem found
+fn validate(paths: &[PathBuf]) -> Result<(), Box<dyn Error>> {
+    let mut failed = 0;
+    for path in paths {
+        // errors name the file already
+        let level = match level::load_level(path) {
+            Ok(level) => level,
+            Err(e) => {
+                failed += 1;
+                println!("{}", e);
+                continue;
+            }
+        };
+
+        let lints = lint::lint(&level);
+        if lints.is_empty() {
+            println!("{}: ok", path.display());
+        } else {
+            failed += 1;
+            for lint in lints {
+                println!("{}: {}", path.display(), lint);
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("{} of {} level files have problems", failed, paths.len()).into());
+    }
 
-    // create context
+    Ok(())
+}
+
+// Opens the window, watching the record if one is given
+fn run(mut game: Game, watch: Option<&GameRecord>) -> Result<(), Box<dyn Error>> {
     let context_builder = ggez::ContextBuilder::new("pegsol", "Charith Ellawala")
-        .window_setup(conf::WindowSetup::default().title("Peg Solitaire"))
-        .window_mode(conf::WindowMode::default().dimensions(700.0, 800.0))
-        .add_resource_path(path::PathBuf::from("./assets"));
+        .window_setup(conf::WindowSetup::default().title(&game.catalogue.title()))
+        .window_mode(
+            conf::WindowMode::default()
+                .dimensions(constants::WINDOW_WIDTH, constants::WINDOW_HEIGHT),
+        )
+        .add_resource_path(path::PathBuf::from(constants::ASSETS_DIR));
 
     let (context, event_loop) = &mut context_builder.build()?;
 
     // load assets into memory
-    resources::load_assets(&mut world, context);
+    resources::load_assets(&mut game.world, context);
 
     // start the event loop
-    let game = &mut Game { world };
-    event::run(context, event_loop, game)
+    if let Some(record) = watch {
+        game.watch_replay(record);
+    }
+    event::run(context, event_loop, &mut game)?;
+    Ok(())
+}
+
+// A saved game brings its own variant, rules and start
+fn saved_board(levels: &mut [Level], record: &GameRecord) -> Result<BoardChoice, Box<dyn Error>> {
+    let current = variants::find(levels, &record.variant)?;
+    levels[current].rules = record.rules;
+    if let Some((x, y)) = record.start {
+        if levels[current].with_start(x, y).is_none() {
+            return Err(format!(
+                "start slot {} of the record is not on the board",
+                notation::slot_name(x, y)
+            )
+            .into());
+        }
+    }
+
+    Ok((current, record.start))
 }
 
 struct Game {
     world: World,
+    catalogue: Catalogue,
+    animation: AnimationSpeed,
+    sticks: gamepad::Sticks,
+}
+
+impl Game {
+    fn new(catalogue: Catalogue, animation: AnimationSpeed) -> Self {
+        // create new World and register components and resources
+        let mut world = World::new();
+        components::register_components(&mut world);
+        world.insert(AssetStore::default());
+        world.insert(MoveHints::default());
+
+        let mut game = Self {
+            world,
+            catalogue,
+            animation,
+            sticks: gamepad::Sticks::default(),
+        };
+        game.start();
+        game
+    }
+
+    // Sets up the board for the current level, discarding any previous game
+    fn start(&mut self) {
+        self.world.delete_all();
+        self.world.maintain();
+
+        let game = self.catalogue.start_game();
+        let board = entities::create_board(&mut self.world, game.shape(), &game.position());
+        self.world.insert(Geometry::new(
+            board.width(),
+            board.height(),
+            board.topology(),
+        ));
+        self.world.insert(GameState::new(board, game));
+        self.world.insert(InputEventQueue::default());
+        self.world.insert(GameEventQueue::default());
+        self.world.insert(MoveQueue::default());
+        self.world.insert(HistoryQueue::default());
+        self.world.insert(ReplayQueue::default());
+        self.world.insert(Solvability::default());
+        self.world.insert(HintRequest::default());
+        self.world.insert(Animation::new(self.animation));
+        self.world.insert(Drag::default());
+        self.world.remove::<Replay>();
+    }
+
+    // Plays the recorded moves on the board
+    fn replay(&mut self, record: &GameRecord) -> Result<(), record::ReplayError> {
+        let result = record.replay(&mut self.world.write_resource::<GameState>().game);
+        entities::sync_board(&self.world);
+        result
+    }
+
+    // Turns the replayed record into a replay that is stepped through from the start
+    fn watch_replay(&mut self, record: &GameRecord) {
+        self.world.write_resource::<GameState>().game.rewind();
+        entities::sync_board(&self.world);
+        self.world
+            .insert(Replay::new(record.jump_count(), record.moves.len()));
+    }
+
+    // Asks for the first jump of a solution from the current position. The hint system points it
+    // out once the solvability check of the position has found it.
+    fn show_hint(&mut self) {
+        if self.is_over() {
+            return;
+        }
+
+        let game_state = self.world.read_resource::<GameState>();
+        let mut hint = self.world.write_resource::<HintRequest>();
+        let position = game_state.game.position();
+        if hint.position == Some(position) && !hint.state.can_ask() {
+            return;
+        }
+
+        *hint = HintRequest {
+            position: Some(position),
+            state: HintState::Searching,
+        };
+    }
+
+    fn is_watching_replay(&self) -> bool {
+        self.world.has_value::<Replay>()
+    }
+
+    fn new_game(&mut self, context: &mut Context) {
+        self.catalogue.next();
+        graphics::set_window_title(context, &self.catalogue.title());
+        self.start();
+    }
+
+    fn save_record(&self) {
+        let record = self.record();
+        match record::save_record(constants::RECORDS_DIR, &record) {
+            Ok(path) => println!("saved game record to {}", path.display()),
+            Err(e) => eprintln!("failed to save game record: {}", e),
+        }
+    }
+
+    fn record(&self) -> GameRecord {
+        let game_state = self.world.read_resource::<GameState>();
+        GameRecord::from_game(
+            &game_state.game,
+            self.catalogue.level(),
+            self.catalogue.start(),
+        )
+    }
+
+    fn is_over(&self) -> bool {
+        self.world.read_resource::<GameState>().game.is_over()
+    }
 }
 
 impl event::EventHandler for Game {
-    fn update(&mut self, _context: &mut Context) -> GameResult {
+    fn update(&mut self, context: &mut Context) -> GameResult {
         let mut is = systems::input::InputSystem;
         is.run_now(&self.world);
 
+        let mut rps = systems::replay::ReplaySystem {
+            delta: timer::delta(context),
+        };
+        rps.run_now(&self.world);
+
+        let mut ans = systems::animation::AnimationSystem {
+            delta: timer::delta(context),
+        };
+        ans.run_now(&self.world);
+
         let mut gs = systems::gameplay::GamePlaySystem;
         gs.run_now(&self.world);
 
+        let mut mhs = systems::move_hints::MoveHintSystem;
+        mhs.run_now(&self.world);
+
+        let mut hs = systems::hint::HintSystem;
+        hs.run_now(&self.world);
+
+        let mut svs = systems::solvability::SolvabilitySystem;
+        svs.run_now(&self.world);
+
         let mut ss = systems::sound::SoundSystem;
         ss.run_now(&self.world);
 
@@ -70,14 +438,65 @@ impl event::EventHandler for Game {
         &mut self,
         context: &mut Context,
         key_code: KeyCode,
-        _key_mod: KeyMods,
+        key_mod: KeyMods,
         _repeat: bool,
     ) {
+        // only the playback can be controlled while watching a replay
+        if self.is_watching_replay() {
+            let action = match key_code {
+                KeyCode::Right | KeyCode::D => ReplayAction::Forward,
+                KeyCode::Left | KeyCode::A => ReplayAction::Back,
+                KeyCode::Space => ReplayAction::TogglePlay,
+                KeyCode::Up | KeyCode::W | KeyCode::Equals | KeyCode::Add => ReplayAction::Faster,
+                KeyCode::Down | KeyCode::S | KeyCode::Minus | KeyCode::Subtract => {
+                    ReplayAction::Slower
+                }
+                KeyCode::Escape => {
+                    context.continuing = false;
+                    return;
+                }
+                _ => return,
+            };
+
+            let mut replay_queue = self.world.write_resource::<ReplayQueue>();
+            replay_queue.enqueue(action);
+            return;
+        }
+
+        let ctrl = key_mod.contains(KeyMods::CTRL);
         let event = match key_code {
+            KeyCode::Z if ctrl => Some(InputEvent::Undo),
+            KeyCode::Y if ctrl => Some(InputEvent::Redo),
+            KeyCode::S if ctrl => {
+                self.save_record();
+                return;
+            }
+            KeyCode::U => Some(InputEvent::Undo),
+            KeyCode::R if self.is_over() => {
+                self.start();
+                return;
+            }
+            KeyCode::R => Some(InputEvent::Redo),
+            KeyCode::N => {
+                self.new_game(context);
+                return;
+            }
+            KeyCode::H => {
+                self.show_hint();
+                return;
+            }
+            KeyCode::M => {
+                self.world.write_resource::<MoveHints>().movable ^= true;
+                return;
+            }
             KeyCode::Up | KeyCode::W => Some(InputEvent::Up),
             KeyCode::Down | KeyCode::S => Some(InputEvent::Down),
             KeyCode::Left | KeyCode::A => Some(InputEvent::Left),
             KeyCode::Right | KeyCode::D => Some(InputEvent::Right),
+            KeyCode::Q => Some(InputEvent::UpLeft),
+            KeyCode::E => Some(InputEvent::UpRight),
+            KeyCode::Z => Some(InputEvent::DownLeft),
+            KeyCode::C => Some(InputEvent::DownRight),
             KeyCode::Return => Some(InputEvent::Enter),
             KeyCode::Escape => {
                 context.continuing = false;
@@ -92,10 +511,49 @@ impl event::EventHandler for Game {
         }
     }
 
+    fn gamepad_button_down_event(&mut self, _ctx: &mut Context, button: Button, _id: GamepadId) {
+        if self.is_watching_replay() {
+            if let Some(action) = gamepad::replay_action(button) {
+                let mut replay_queue = self.world.write_resource::<ReplayQueue>();
+                replay_queue.enqueue(action);
+            }
+            return;
+        }
+
+        if let Some(event) = gamepad::input_event(button) {
+            let mut input_queue = self.world.write_resource::<InputEventQueue>();
+            input_queue.enqueue(event);
+        }
+    }
+
+    fn gamepad_axis_event(&mut self, _ctx: &mut Context, axis: Axis, value: f32, id: GamepadId) {
+        // the stick is followed during a replay too, so that it is not taken as pushed afterwards
+        let step = self.sticks.step(id, axis, value);
+        if let Some(event) = step.filter(|_| !self.is_watching_replay()) {
+            let mut input_queue = self.world.write_resource::<InputEventQueue>();
+            input_queue.enqueue(event);
+        }
+    }
+
     fn mouse_button_down_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
-        if button == MouseButton::Left {
+        if button == MouseButton::Left && !self.is_watching_replay() {
             let mut input_queue = self.world.write_resource::<InputEventQueue>();
             input_queue.enqueue(InputEvent::MouseClick { x, y });
         }
     }
+
+    fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        if button == MouseButton::Left && !self.is_watching_replay() {
+            let mut input_queue = self.world.write_resource::<InputEventQueue>();
+            input_queue.enqueue(InputEvent::MouseRelease { x, y });
+        }
+    }
+
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
+        self.world.write_resource::<Drag>().move_to(x, y);
+        if !self.is_watching_replay() {
+            let mut input_queue = self.world.write_resource::<InputEventQueue>();
+            input_queue.move_cursor(x, y);
+        }
+    }
 }
@@ -0,0 +1,356 @@
+use crate::constants::TILE_SIZE;
+use crate::resources::{peg_layer, Geometry, ImageType, FONT_NAME};
+use gif::SetParameter;
+use image::{imageops, GenericImageView, Rgba, RgbaImage};
+use pegsol_engine::game::{Game, GameStatus};
+use pegsol_engine::notation;
+use pegsol_engine::position::{Jump, Shape};
+use rusttype::{point, Font, Scale};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{fmt, fs, io};
+
+// Drawing of positions to images, without a window or a GPU. Uses the same tiles and placement
+// as the window, so a picture looks like the board in play.
+
+// Space around the board, in pixels
+const MARGIN: f32 = 10.0;
+const BACKGROUND: Rgba<u8> = Rgba([0, 0, 0, 255]);
+const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const LABEL_SIZE: f32 = 18.0;
+// Distance of a label from the top-left corner of its tile
+const LABEL_INSET: f32 = 6.0;
+
+// Timing of animations: each position is held before its jump is played in a few frames
+const PAUSE: Duration = Duration::from_millis(500);
+const JUMP_FRAMES: u32 = 8;
+const JUMP_FRAME: Duration = Duration::from_millis(40);
+const RESULT_PAUSE: Duration = Duration::from_millis(3000);
+// The result is written over the final board, darkened to this fraction of its brightness
+const RESULT_DIMMING: f32 = 0.35;
+const STATUS_SIZE: f32 = 64.0;
+const SCORE_SIZE: f32 = 32.0;
+// Colour quantisation effort of GIF frames, from 1 (best) to 30 (fastest)
+const GIF_SPEED: i32 = 10;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RenderOptions {
+    // Highlight the slots of the last jump: where it started and the peg it took in the
+    // highlight colour, where it landed as selected
+    pub last_move: bool,
+    // Name every slot in its corner
+    pub labels: bool,
+}
+
+#[derive(Debug)]
+pub enum RenderError {
+    Io(PathBuf, io::Error),
+    Image(PathBuf, image::ImageError),
+    Font(PathBuf, rusttype::Error),
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RenderError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            RenderError::Image(path, e) => write!(f, "{}: {}", path.display(), e),
+            RenderError::Font(path, e) => write!(f, "{}: {}", path.display(), e),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+// An image of an animation, shown for the given time
+pub struct Frame {
+    pub image: RgbaImage,
+    pub delay: Duration,
+}
+
+pub struct Renderer {
+    tiles: HashMap<ImageType, RgbaImage>,
+    // The peg alone, drawn over empty slots while it moves
+    peg: RgbaImage,
+    font: Font<'static>,
+}
+
+impl Renderer {
+    // Loads the slot images and the font from the assets directory
+    pub fn load<P: AsRef<Path>>(assets_dir: P) -> Result<Self, RenderError> {
+        let assets_dir = assets_dir.as_ref();
+
+        let mut tiles = HashMap::new();
+        for &tile in ImageType::SLOTS.iter() {
+            let path = assets_dir.join("images").join(tile.image_name());
+            let image = image::open(&path).map_err(|e| RenderError::Image(path, e))?;
+            tiles.insert(tile, image.to_rgba());
+        }
+
+        let path = assets_dir.join("fonts").join(FONT_NAME);
+        let bytes = fs::read(&path).map_err(|e| RenderError::Io(path.clone(), e))?;
+        let font = Font::from_bytes(bytes).map_err(|e| RenderError::Font(path, e))?;
+
+        let peg = peg_layer(
+            &tiles[&ImageType::OccupiedNormal],
+            &tiles[&ImageType::UnoccupiedNormal],
+        );
+
+        Ok(Self { tiles, peg, font })
+    }
+
+    // Draws the board of the game as it is now
+    pub fn render(&self, game: &Game, options: RenderOptions) -> RgbaImage {
+        let position = game.position();
+        let last_jump = game
+            .history()
+            .last()
+            .map(|entry| entry.jump)
+            .filter(|_| options.last_move);
+
+        let (canvas, _) = self.draw_board(game.shape(), options.labels, |i| {
+            let (selected, highlighted) = match last_jump {
+                Some(jump) if jump.to == i => (true, false),
+                Some(jump) if jump.from == i || jump.over == i => (false, true),
+                _ => (false, false),
+            };
+            ImageType::slot(position.is_occupied(i), selected, highlighted)
+        });
+        canvas
+    }
+
+    // Plays the jumps of the game from its start, one after another, and ends on its result
+    pub fn animate(&self, mut game: Game) -> Vec<Frame> {
+        let jumps: Vec<Jump> = game.history().entries().iter().map(|e| e.jump).collect();
+        game.rewind();
+
+        let mut frames = Vec::new();
+        for jump in &jumps {
+            frames.push(Frame {
+                image: self.render(&game, RenderOptions::default()),
+                delay: PAUSE,
+            });
+            frames.extend(self.jump_frames(&game, jump));
+            game.redo();
+        }
+
+        let last = self.render(&game, RenderOptions::default());
+        let result = self.result_image(&game, last.clone());
+        frames.push(Frame {
+            image: last,
+            delay: PAUSE,
+        });
+        frames.push(Frame {
+            image: result,
+            delay: RESULT_PAUSE,
+        });
+        frames
+    }
+
+    // The peg moving from where the jump starts to where it lands, as the peg it takes fades out
+    fn jump_frames(&self, game: &Game, jump: &Jump) -> Vec<Frame> {
+        let position = game.position();
+        let (board, geometry) = self.draw_board(game.shape(), false, |i| {
+            let occupied = position.is_occupied(i) && i != jump.from && i != jump.over;
+            ImageType::slot(occupied, false, false)
+        });
+
+        let holes = game.shape().holes();
+        let place = |i: usize| geometry.slot_position(holes[i].0, holes[i].1);
+        let (from, over, to) = (place(jump.from), place(jump.over), place(jump.to));
+
+        (0..JUMP_FRAMES)
+            .map(|k| {
+                let t = k as f32 / JUMP_FRAMES as f32;
+                let mut image = board.clone();
+                overlay_faded(&mut image, &self.peg, over, 1.0 - t);
+                let x = from.0 + (to.0 - from.0) * t;
+                let y = from.1 + (to.1 - from.1) * t;
+                overlay_faded(&mut image, &self.peg, (x, y), 1.0);
+                Frame {
+                    image,
+                    delay: JUMP_FRAME,
+                }
+            })
+            .collect()
+    }
+
+    // The final board, dimmed, with the outcome and the score written over it
+    fn result_image(&self, game: &Game, mut image: RgbaImage) -> RgbaImage {
+        for pixel in image.pixels_mut() {
+            for c in 0..3 {
+                pixel[c] = (f32::from(pixel[c]) * RESULT_DIMMING) as u8;
+            }
+        }
+
+        let status = match game.status() {
+            GameStatus::OnGoing => None,
+            GameStatus::Completed => Some(("GAME OVER", Rgba([255, 0, 0, 255]))),
+            GameStatus::Solved => Some(("SOLVED", Rgba([0, 255, 0, 255]))),
+        };
+        let pegs = match game.peg_count() {
+            1 => "1 peg left".to_string(),
+            n => format!("{} pegs left", n),
+        };
+        let score = format!("{} moves, {}", game.move_count(), pegs);
+
+        let middle = image.height() as f32 / 2.0;
+        if let Some((text, colour)) = status {
+            let y = middle - STATUS_SIZE;
+            self.draw_centred(&mut image, text, y, STATUS_SIZE, colour);
+        }
+        self.draw_centred(&mut image, &score, middle + 8.0, SCORE_SIZE, WHITE);
+        image
+    }
+
+    // Draws the slots of the board in an image that fits it, with the given tile for each hole
+    fn draw_board<F: Fn(usize) -> ImageType>(
+        &self,
+        shape: &Shape,
+        labels: bool,
+        tile: F,
+    ) -> (RgbaImage, Geometry) {
+        let geometry = Geometry::unscaled(shape.height(), shape.topology(), MARGIN);
+        let width = shape.width() as f32 * TILE_SIZE + 2.0 * MARGIN;
+        let height = shape.height() as f32 * TILE_SIZE + 2.0 * MARGIN;
+        let mut canvas = RgbaImage::from_pixel(width as u32, height as u32, BACKGROUND);
+
+        for (i, &(sx, sy)) in shape.holes().iter().enumerate() {
+            let (x, y) = geometry.slot_position(sx, sy);
+            imageops::overlay(&mut canvas, &self.tiles[&tile(i)], x as u32, y as u32);
+
+            if labels {
+                let name = notation::slot_name(sx, sy);
+                let (x, y) = (x + LABEL_INSET, y + LABEL_INSET);
+                self.draw_text(&mut canvas, &name, (x, y), LABEL_SIZE, WHITE);
+            }
+        }
+
+        (canvas, geometry)
+    }
+
+    // Draws a line of text across the middle of the image, with its top at the given height
+    fn draw_centred(
+        &self,
+        canvas: &mut RgbaImage,
+        text: &str,
+        y: f32,
+        size: f32,
+        colour: Rgba<u8>,
+    ) {
+        let scale = Scale::uniform(size);
+        let width = self
+            .font
+            .layout(text, scale, point(0.0, 0.0))
+            .last()
+            .map_or(0.0, |g| {
+                g.position().x + g.unpositioned().h_metrics().advance_width
+            });
+        let x = (canvas.width() as f32 - width) / 2.0;
+        self.draw_text(canvas, text, (x, y), size, colour);
+    }
+
+    // Blends the text onto the image, with its top-left corner at the given point
+    fn draw_text(
+        &self,
+        canvas: &mut RgbaImage,
+        text: &str,
+        (x, y): (f32, f32),
+        size: f32,
+        colour: Rgba<u8>,
+    ) {
+        let scale = Scale::uniform(size);
+        let ascent = self.font.v_metrics(scale).ascent;
+        for glyph in self.font.layout(text, scale, point(x, y + ascent)) {
+            let bounds = match glyph.pixel_bounding_box() {
+                Some(bounds) => bounds,
+                None => continue,
+            };
+
+            glyph.draw(|gx, gy, coverage| {
+                let px = bounds.min.x + gx as i32;
+                let py = bounds.min.y + gy as i32;
+                if px < 0 || py < 0 || px as u32 >= canvas.width() || py as u32 >= canvas.height() {
+                    return;
+                }
+
+                let pixel = canvas.get_pixel_mut(px as u32, py as u32);
+                for c in 0..3 {
+                    let blended =
+                        f32::from(pixel[c]) * (1.0 - coverage) + f32::from(colour[c]) * coverage;
+                    pixel[c] = blended.round() as u8;
+                }
+            });
+        }
+    }
+}
+
+pub fn save_png<P: AsRef<Path>>(image: &RgbaImage, path: P) -> Result<(), RenderError> {
+    let path = path.as_ref();
+    image
+        .save(path)
+        .map_err(|e| RenderError::Io(path.to_path_buf(), e))
+}
+
+// Writes the frames as a GIF that loops forever. Each frame only holds the area that changed
+// since the one before, which is usually the few slots of a jump.
+pub fn save_gif<P: AsRef<Path>>(frames: &[Frame], path: P) -> Result<(), RenderError> {
+    let path = path.as_ref();
+    let io_error = |e| RenderError::Io(path.to_path_buf(), e);
+
+    let (width, height) = frames
+        .first()
+        .map_or((0, 0), |f| (f.image.width(), f.image.height()));
+    let file = fs::File::create(path).map_err(io_error)?;
+    let mut encoder = gif::Encoder::new(io::BufWriter::new(file), width as u16, height as u16, &[])
+        .map_err(io_error)?;
+    encoder.set(gif::Repeat::Infinite).map_err(io_error)?;
+
+    let mut previous: Option<&RgbaImage> = None;
+    for frame in frames {
+        let (left, top, w, h) = match previous {
+            Some(previous) => changed_area(previous, &frame.image),
+            None => (0, 0, width, height),
+        };
+        previous = Some(&frame.image);
+
+        let area = frame.image.view(left, top, w, h).to_image();
+        let mut pixels = area.into_raw();
+        let mut gif_frame = gif::Frame::from_rgba_speed(w as u16, h as u16, &mut pixels, GIF_SPEED);
+        gif_frame.left = left as u16;
+        gif_frame.top = top as u16;
+        gif_frame.dispose = gif::DisposalMethod::Keep;
+        // in hundredths of a second
+        gif_frame.delay = (frame.delay.as_millis() / 10) as u16;
+        encoder.write_frame(&gif_frame).map_err(io_error)?;
+    }
+
+    Ok(())
+}
+
+// Smallest rectangle holding every pixel that differs between the images, as left, top, width
+// and height. Identical images still give a single pixel, so that there is a frame to show.
+fn changed_area(before: &RgbaImage, after: &RgbaImage) -> (u32, u32, u32, u32) {
+    let mut min = (after.width(), after.height());
+    let mut max = (0, 0);
+    for (x, y, pixel) in after.enumerate_pixels() {
+        if before.get_pixel(x, y) != pixel {
+            min = (min.0.min(x), min.1.min(y));
+            max = (max.0.max(x), max.1.max(y));
+        }
+    }
+
+    if min.0 > max.0 {
+        return (0, 0, 1, 1);
+    }
+    (min.0, min.1, max.0 - min.0 + 1, max.1 - min.1 + 1)
+}
+
+// Blends the image onto the canvas at the given point, at a fraction of its opacity
+fn overlay_faded(canvas: &mut RgbaImage, top: &RgbaImage, (x, y): (f32, f32), opacity: f32) {
+    let mut faded = top.clone();
+    for pixel in faded.pixels_mut() {
+        pixel[3] = (f32::from(pixel[3]) * opacity) as u8;
+    }
+    imageops::overlay(canvas, &faded, x.round() as u32, y.round() as u32);
+}
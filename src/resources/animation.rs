@@ -0,0 +1,130 @@
+use std::f32::consts::PI;
+use std::str::FromStr;
+use std::time::Duration;
+
+// Height of the arc the moving peg follows, in tiles
+const ARC_HEIGHT: f32 = 0.5;
+
+// How fast jumps are played out on the board
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AnimationSpeed {
+    Off,
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+}
+
+impl AnimationSpeed {
+    // Time a jump takes, if jumps are animated at all
+    pub fn duration(self) -> Option<Duration> {
+        match self {
+            AnimationSpeed::Off => None,
+            AnimationSpeed::Slow => Some(Duration::from_millis(500)),
+            AnimationSpeed::Normal => Some(Duration::from_millis(250)),
+            AnimationSpeed::Fast => Some(Duration::from_millis(125)),
+        }
+    }
+}
+
+impl FromStr for AnimationSpeed {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(AnimationSpeed::Off),
+            "slow" => Ok(AnimationSpeed::Slow),
+            "normal" => Ok(AnimationSpeed::Normal),
+            "fast" => Ok(AnimationSpeed::Fast),
+            _ => Err(()),
+        }
+    }
+}
+
+// A jump being played out. The slots already hold the position after the jump, so they are drawn
+// empty while the pegs are drawn on top of them.
+pub struct Tween {
+    // Where the moving peg starts and lands
+    pub from: (usize, usize),
+    pub to: (usize, usize),
+    pub over: (usize, usize),
+    // The peg jumped over goes away, or comes back when a jump is taken back
+    pub capturing: bool,
+    elapsed: Duration,
+    duration: Duration,
+}
+
+impl Tween {
+    // Fraction of the jump played so far
+    pub fn progress(&self) -> f32 {
+        (self.elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+
+    // How far above its straight path the moving peg is, in tiles
+    pub fn lift(&self) -> f32 {
+        ARC_HEIGHT * (PI * self.progress()).sin()
+    }
+
+    // Size and opacity of the peg jumped over, from 1 when it is whole to 0 when it is gone
+    pub fn captured(&self) -> f32 {
+        if self.capturing {
+            1.0 - self.progress()
+        } else {
+            self.progress()
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+// Playing out of jumps on the board. Moves wait in their queues until the jump before them is
+// over, so the board never changes under a jump being shown.
+#[derive(Default)]
+pub struct Animation {
+    pub speed: AnimationSpeed,
+    pub tween: Option<Tween>,
+}
+
+impl Animation {
+    pub fn new(speed: AnimationSpeed) -> Self {
+        Self { speed, tween: None }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.tween.is_some()
+    }
+
+    // Shows a peg jumping between the slots, unless animation is off
+    pub fn start(
+        &mut self,
+        from: (usize, usize),
+        over: (usize, usize),
+        to: (usize, usize),
+        capturing: bool,
+    ) {
+        self.tween = self.speed.duration().map(|duration| Tween {
+            from,
+            to,
+            over,
+            capturing,
+            elapsed: Duration::default(),
+            duration,
+        });
+    }
+
+    // Stops showing the current jump, leaving the board as it is after it
+    pub fn skip(&mut self) {
+        self.tween = None;
+    }
+
+    pub fn advance(&mut self, delta: Duration) {
+        if let Some(tween) = &mut self.tween {
+            tween.elapsed += delta;
+            if tween.is_done() {
+                self.tween = None;
+            }
+        }
+    }
+}
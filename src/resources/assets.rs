@@ -2,6 +2,7 @@ use ggez::audio;
 use ggez::audio::SoundSource;
 use ggez::graphics::{Font, Image};
 use ggez::Context;
+use image::{Rgba, RgbaImage};
 use specs::{World, WorldExt};
 use std::collections::HashMap;
 use std::path::Path;
@@ -26,7 +27,32 @@ pub enum ImageType {
 }
 
 impl ImageType {
-    fn image_name(&self) -> &str {
+    pub const SLOTS: [ImageType; 8] = [
+        ImageType::OccupiedSelectedHighlighted,
+        ImageType::OccupiedSelected,
+        ImageType::OccupiedHighlighted,
+        ImageType::OccupiedNormal,
+        ImageType::UnoccupiedSelectedHighlighted,
+        ImageType::UnoccupiedSelected,
+        ImageType::UnoccupiedHighlighted,
+        ImageType::UnoccupiedNormal,
+    ];
+
+    // Image of a slot in the given state
+    pub fn slot(occupied: bool, selected: bool, highlighted: bool) -> Self {
+        match (occupied, selected, highlighted) {
+            (true, true, true) => ImageType::OccupiedSelectedHighlighted,
+            (true, true, false) => ImageType::OccupiedSelected,
+            (true, false, true) => ImageType::OccupiedHighlighted,
+            (true, false, false) => ImageType::OccupiedNormal,
+            (false, true, true) => ImageType::UnoccupiedSelectedHighlighted,
+            (false, true, false) => ImageType::UnoccupiedSelected,
+            (false, false, true) => ImageType::UnoccupiedHighlighted,
+            (false, false, false) => ImageType::UnoccupiedNormal,
+        }
+    }
+
+    pub fn image_name(&self) -> &str {
         match self {
             ImageType::OccupiedSelectedHighlighted => "slot_occupied_selected_highlighted.png",
             ImageType::OccupiedSelected => "slot_occupied_selected.png",
@@ -59,37 +85,20 @@ impl SoundType {
     }
 }
 
+pub const FONT_NAME: &str = "Roboto-Bold.ttf";
+
+#[derive(Default)]
 pub struct AssetStore {
     images: HashMap<ImageType, Image>,
     sounds: HashMap<SoundType, audio::Source>,
+    // The peg alone, drawn over empty slots while it jumps
+    peg: Option<Image>,
     font: Font,
 }
 
-impl Default for AssetStore {
-    fn default() -> Self {
-        Self {
-            images: HashMap::new(),
-            sounds: HashMap::new(),
-            font: Font::default(),
-        }
-    }
-}
-
 impl AssetStore {
     fn load(&mut self, context: &mut Context) {
-        for s in [
-            ImageType::OccupiedSelectedHighlighted,
-            ImageType::OccupiedSelected,
-            ImageType::OccupiedHighlighted,
-            ImageType::OccupiedNormal,
-            ImageType::UnoccupiedSelectedHighlighted,
-            ImageType::UnoccupiedSelected,
-            ImageType::UnoccupiedHighlighted,
-            ImageType::UnoccupiedNormal,
-            ImageType::Header,
-        ]
-        .iter()
-        {
+        for s in ImageType::SLOTS.iter().chain(&[ImageType::Header]) {
             let image_path = Path::new("/images").join(s.image_name());
             self.images.insert(
                 *s,
@@ -97,6 +106,14 @@ impl AssetStore {
             );
         }
 
+        let occupied = self.tile_pixels(context, ImageType::OccupiedNormal);
+        let unoccupied = self.tile_pixels(context, ImageType::UnoccupiedNormal);
+        let peg = peg_layer(&occupied, &unoccupied);
+        self.peg = Some(
+            Image::from_rgba8(context, peg.width() as u16, peg.height() as u16, &peg)
+                .expect("unable to create peg image"),
+        );
+
         for a in [
             SoundType::CorrectMove,
             SoundType::IncorrectMove,
@@ -112,15 +129,28 @@ impl AssetStore {
         }
 
         self.font =
-            Font::new(context, Path::new("/fonts/Roboto-Bold.ttf")).expect("unable to load font");
+            Font::new(context, Path::new("/fonts").join(FONT_NAME)).expect("unable to load font");
     }
 
     pub fn image(&self, s: ImageType) -> Image {
         self.images.get(&s).unwrap().clone()
     }
 
+    pub fn peg(&self) -> Image {
+        self.peg.clone().expect("peg image not loaded")
+    }
+
+    fn tile_pixels(&self, context: &mut Context, s: ImageType) -> RgbaImage {
+        let image = self.image(s);
+        let pixels = image
+            .to_rgba8(context)
+            .expect("unable to read image pixels");
+        RgbaImage::from_raw(image.width().into(), image.height().into(), pixels)
+            .expect("image pixels do not match its size")
+    }
+
     pub fn font(&self) -> Font {
-        self.font.clone()
+        self.font
     }
 
     pub fn play_sound(&mut self, s: SoundType) {
@@ -131,3 +161,16 @@ impl AssetStore {
             .play_detached();
     }
 }
+
+// The parts of an occupied slot that differ from an empty one, which is the peg
+pub fn peg_layer(occupied: &RgbaImage, empty: &RgbaImage) -> RgbaImage {
+    let mut peg = occupied.clone();
+    for (x, y, pixel) in peg.enumerate_pixels_mut() {
+        let same = x < empty.width() && y < empty.height() && *empty.get_pixel(x, y) == *pixel;
+        if same {
+            *pixel = Rgba([0, 0, 0, 0]);
+        }
+    }
+
+    peg
+}
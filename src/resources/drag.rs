@@ -0,0 +1,24 @@
+use specs::Entity;
+
+// A peg being dragged with the mouse. Its slot keeps the peg until the drop is played as a move,
+// so a drop that is not a legal jump puts it straight back.
+pub struct DraggedPeg {
+    pub entity: Entity,
+    pub slot: (usize, usize),
+    // Where the peg was grabbed, relative to the top-left corner of its tile
+    pub grab: (f32, f32),
+    pub cursor: (f32, f32),
+}
+
+#[derive(Default)]
+pub struct Drag {
+    pub peg: Option<DraggedPeg>,
+}
+
+impl Drag {
+    pub fn move_to(&mut self, x: f32, y: f32) {
+        if let Some(peg) = &mut self.peg {
+            peg.cursor = (x, y);
+        }
+    }
+}
@@ -1,26 +1,14 @@
-use crate::constants::PEG_COUNT;
 use crate::entities::Board;
-
-#[derive(Debug, PartialEq)]
-pub enum GameStatus {
-    OnGoing,
-    Completed,
-}
+use pegsol_engine::game::Game;
 
 pub struct GameState {
     pub board: Board,
-    pub status: GameStatus,
-    pub move_count: u8,
-    pub peg_count: u8,
+    // The game on the board, which the `Occupied` components follow
+    pub game: Game,
 }
 
 impl GameState {
-    pub fn new(board: Board) -> Self {
-        Self {
-            board,
-            status: GameStatus::OnGoing,
-            move_count: 0,
-            peg_count: PEG_COUNT,
-        }
+    pub fn new(board: Board, game: Game) -> Self {
+        Self { board, game }
     }
 }
@@ -0,0 +1,133 @@
+use crate::constants::{
+    PADDING_BOTTOM, PADDING_LEFT, PADDING_RIGHT, PADDING_TOP, TILE_SIZE, WINDOW_HEIGHT,
+    WINDOW_WIDTH,
+};
+use pegsol_engine::topology::Topology;
+
+// Screen placement of the board slots
+pub struct Geometry {
+    pub tile_size: f32,
+    origin_x: f32,
+    origin_y: f32,
+    height: usize,
+    topology: Topology,
+}
+
+impl Geometry {
+    pub fn new(width: usize, height: usize, topology: Topology) -> Self {
+        let area_width = WINDOW_WIDTH - PADDING_LEFT - PADDING_RIGHT;
+        let area_height = WINDOW_HEIGHT - PADDING_TOP - PADDING_BOTTOM;
+
+        let tile_size = TILE_SIZE
+            .min(area_width / width.max(1) as f32)
+            .min(area_height / height.max(1) as f32);
+
+        // centre the board in the available area
+        Self {
+            tile_size,
+            origin_x: PADDING_LEFT + (area_width - tile_size * width as f32) / 2.0,
+            origin_y: PADDING_TOP + (area_height - tile_size * height as f32) / 2.0,
+            height,
+            topology,
+        }
+    }
+
+    // Tiles at full size, with the board at the given distance from the top-left corner, for
+    // drawing to an image of the board alone
+    pub fn unscaled(height: usize, topology: Topology, margin: f32) -> Self {
+        Self {
+            tile_size: TILE_SIZE,
+            origin_x: margin,
+            origin_y: margin,
+            height,
+            topology,
+        }
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.tile_size / TILE_SIZE
+    }
+
+    // Top-left corner of the given slot
+    pub fn slot_position(&self, x: usize, y: usize) -> (f32, f32) {
+        let offset = self.topology.row_offset(y, self.height);
+        (
+            self.origin_x + (x as f32 + offset) * self.tile_size,
+            self.origin_y + y as f32 * self.tile_size,
+        )
+    }
+
+    // Slot coordinates under the given screen position
+    pub fn slot_at(&self, px: f32, py: f32) -> Option<(usize, usize)> {
+        if py < self.origin_y {
+            return None;
+        }
+
+        let y = ((py - self.origin_y) / self.tile_size) as usize;
+        if y >= self.height {
+            return None;
+        }
+
+        let row_x = self.origin_x + self.topology.row_offset(y, self.height) * self.tile_size;
+        if px < row_x {
+            return None;
+        }
+
+        let x = ((px - row_x) / self.tile_size) as usize;
+        Some((x, y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Middle of the tile of the given slot
+    fn centre(geometry: &Geometry, x: usize, y: usize) -> (f32, f32) {
+        let (left, top) = geometry.slot_position(x, y);
+        (
+            left + geometry.tile_size / 2.0,
+            top + geometry.tile_size / 2.0,
+        )
+    }
+
+    #[test]
+    fn finds_the_slot_under_every_tile() {
+        for &(width, height, topology) in &[(7, 7, Topology::Square), (5, 5, Topology::Triangular)]
+        {
+            let geometry = Geometry::new(width, height, topology);
+            for y in 0..height {
+                for x in (0..width).filter(|&x| topology.contains(x, y)) {
+                    let (px, py) = centre(&geometry, x, y);
+                    assert_eq!(geometry.slot_at(px, py), Some((x, y)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shifts_the_rows_of_triangular_boards() {
+        // rows are shifted right by half a tile for every row below them
+        let geometry = Geometry::unscaled(5, Topology::Triangular, 10.0);
+        assert_eq!(geometry.slot_position(0, 0), (10.0 + 2.0 * TILE_SIZE, 10.0));
+        assert_eq!(geometry.slot_position(0, 4), (10.0, 10.0 + 4.0 * TILE_SIZE));
+
+        // just left of the top slot is outside the top row, but on the first slot of the next
+        let px = 10.0 + 2.0 * TILE_SIZE - 10.0;
+        assert_eq!(geometry.slot_at(px, 10.0 + TILE_SIZE / 2.0), None);
+        assert_eq!(geometry.slot_at(px, 10.0 + TILE_SIZE * 1.5), Some((0, 1)));
+        assert_eq!(geometry.slot_at(15.0, 10.0 + TILE_SIZE * 4.5), Some((0, 4)));
+    }
+
+    #[test]
+    fn finds_no_slot_off_the_board() {
+        let geometry = Geometry::unscaled(7, Topology::Square, 10.0);
+        assert_eq!(geometry.slot_at(50.0, 5.0), None);
+        assert_eq!(geometry.slot_at(5.0, 50.0), None);
+        assert_eq!(geometry.slot_at(50.0, 10.0 + 7.0 * TILE_SIZE + 1.0), None);
+        assert_eq!(
+            geometry.slot_at(50.0, 10.0 + 7.0 * TILE_SIZE - 1.0),
+            Some((0, 6))
+        );
+    }
+}
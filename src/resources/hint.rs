@@ -0,0 +1,11 @@
+use pegsol_engine::position::Position;
+use pegsol_frontend::hint::HintState;
+
+// Hint the player asked for. It comes from the solvability check of the position, so it is shown
+// once the check is done.
+#[derive(Default)]
+pub struct HintRequest {
+    // Position the hint is for
+    pub position: Option<Position>,
+    pub state: HintState,
+}
@@ -1,7 +1,21 @@
+pub use self::animation::*;
 pub use self::assets::*;
+pub use self::drag::*;
 pub use self::game_state::*;
+pub use self::geometry::*;
+pub use self::hint::*;
+pub use self::move_hints::*;
 pub use self::queues::*;
+pub use self::replay::*;
+pub use self::solvability::*;
 
+mod animation;
 mod assets;
+mod drag;
 mod game_state;
+mod geometry;
+mod hint;
+mod move_hints;
 mod queues;
+mod replay;
+mod solvability;
@@ -0,0 +1,6 @@
+// Which moves are pointed out on the board. The landing slots of the selected peg always are.
+#[derive(Default)]
+pub struct MoveHints {
+    // Mark every peg that can jump
+    pub movable: bool,
+}
@@ -14,7 +14,11 @@ impl<T> Queue<T> {
         self.0.pop_front()
     }
 
-    pub fn drain(&mut self) -> std::collections::vec_deque::Drain<T> {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn drain(&mut self) -> std::collections::vec_deque::Drain<'_, T> {
         self.0.drain(..)
     }
 }
@@ -22,12 +26,38 @@ impl<T> Queue<T> {
 // Queue for handling input events
 #[derive(Debug)]
 pub enum InputEvent {
-    MouseClick { x: f32, y: f32 },
+    MouseClick {
+        x: f32,
+        y: f32,
+    },
+    // The cursor moved, which highlights the slot under it
+    MouseMotion {
+        x: f32,
+        y: f32,
+    },
+    // End of a press, which drops a dragged peg
+    MouseRelease {
+        x: f32,
+        y: f32,
+    },
     Up,
     Down,
     Left,
     Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
     Enter,
+    // Drop the selection without moving
+    Deselect,
+    Undo,
+    Redo,
+    // Select the peg at `from` and move the cursor to where it should land
+    Hint {
+        from: (usize, usize),
+        to: (usize, usize),
+    },
 }
 
 pub type InputEventQueue = Queue<InputEvent>;
@@ -38,10 +68,26 @@ impl Default for InputEventQueue {
     }
 }
 
+impl InputEventQueue {
+    // Queues the new cursor position. The mouse moves many times a frame, so a position that has
+    // not been handled yet is replaced rather than queued behind.
+    pub fn move_cursor(&mut self, x: f32, y: f32) {
+        match self.0.back_mut() {
+            Some(InputEvent::MouseMotion { x: px, y: py }) => {
+                *px = x;
+                *py = y;
+            }
+            _ => self.enqueue(InputEvent::MouseMotion { x, y }),
+        }
+    }
+}
+
 // Queue for handling game moves
 pub struct Move {
     pub prev: Entity,
     pub curr: Entity,
+    // The peg was dragged to where it lands, so there is no jump left to show
+    pub dropped: bool,
 }
 
 pub type MoveQueue = Queue<Move>;
@@ -52,6 +98,39 @@ impl Default for MoveQueue {
     }
 }
 
+// Queue for stepping through the move history
+#[derive(Debug)]
+pub enum HistoryAction {
+    Undo,
+    Redo,
+}
+
+pub type HistoryQueue = Queue<HistoryAction>;
+
+impl Default for HistoryQueue {
+    fn default() -> Self {
+        Self(VecDeque::new())
+    }
+}
+
+// Queue for controlling the playback of a replay
+#[derive(Debug)]
+pub enum ReplayAction {
+    Forward,
+    Back,
+    TogglePlay,
+    Faster,
+    Slower,
+}
+
+pub type ReplayQueue = Queue<ReplayAction>;
+
+impl Default for ReplayQueue {
+    fn default() -> Self {
+        Self(VecDeque::new())
+    }
+}
+
 // Queue for handling game events
 #[derive(Debug)]
 pub enum GameEvent {
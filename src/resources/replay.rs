@@ -0,0 +1,37 @@
+use std::time::Duration;
+
+const MIN_INTERVAL: Duration = Duration::from_millis(125);
+const MAX_INTERVAL: Duration = Duration::from_secs(4);
+
+// Playback of a recorded game. It is only present in the world while a replay is being watched.
+//
+// The recorded jumps are kept in the move history, so stepping through the replay is done by
+// undoing and redoing them.
+pub struct Replay {
+    pub jump_count: usize,
+    pub move_count: usize,
+    pub playing: bool,
+    // Time between jumps when playing automatically
+    pub interval: Duration,
+    pub elapsed: Duration,
+}
+
+impl Replay {
+    pub fn new(jump_count: usize, move_count: usize) -> Self {
+        Self {
+            jump_count,
+            move_count,
+            playing: false,
+            interval: Duration::from_secs(1),
+            elapsed: Duration::default(),
+        }
+    }
+
+    pub fn faster(&mut self) {
+        self.interval = (self.interval / 2).max(MIN_INTERVAL);
+    }
+
+    pub fn slower(&mut self) {
+        self.interval = (self.interval * 2).min(MAX_INTERVAL);
+    }
+}
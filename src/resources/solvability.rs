@@ -0,0 +1,34 @@
+use pegsol_engine::background::{BackgroundSearch, Hint};
+use pegsol_engine::position::Position;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum SolvabilityStatus {
+    // Nothing is known about the position, e.g. before the first check or when the position is
+    // too hard to check in the background
+    #[default]
+    Unknown,
+    Checking,
+    // The position can be reduced to a single peg, and `centre` tells whether it is known to end
+    // in the middle of the board
+    Solvable {
+        centre: bool,
+    },
+    // `reason` explains why, when an invariant proves it without searching
+    Unsolvable {
+        reason: Option<String>,
+    },
+}
+
+// Whether the position on the board can still be solved
+#[derive(Default)]
+pub struct Solvability {
+    pub status: SolvabilityStatus,
+    // Position the status is about
+    pub position: Option<Position>,
+    // First jump toward the goal of the game, once the check is done
+    pub hint: Option<Hint>,
+    pub search: Option<BackgroundSearch<Check>>,
+}
+
+// Status of the position, and the hint for it
+pub type Check = (SolvabilityStatus, Hint);
@@ -0,0 +1,17 @@
+use crate::resources::*;
+use specs::{System, WriteExpect};
+use std::time::Duration;
+
+// Moves the jump being shown along
+pub struct AnimationSystem {
+    // Time since the previous frame
+    pub delta: Duration,
+}
+
+impl<'a> System<'a> for AnimationSystem {
+    type SystemData = WriteExpect<'a, Animation>;
+
+    fn run(&mut self, mut animation: Self::SystemData) {
+        animation.advance(self.delta);
+    }
+}
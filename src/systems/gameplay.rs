@@ -1,8 +1,9 @@
 use crate::components::*;
-use crate::constants::BOARD_LEN;
 use crate::resources::*;
-use specs::{Join, ReadStorage, System, WriteExpect, WriteStorage};
+use pegsol_engine::position::Jump;
+use specs::{ReadStorage, System, WriteExpect, WriteStorage};
 
+// Passes the moves of the player on to the game and keeps the slots in line with its position
 pub struct GamePlaySystem;
 
 impl<'a> System<'a> for GamePlaySystem {
@@ -10,125 +11,124 @@ impl<'a> System<'a> for GamePlaySystem {
         ReadStorage<'a, Slot>,
         WriteExpect<'a, GameState>,
         WriteStorage<'a, Occupied>,
+        WriteStorage<'a, Selected>,
         WriteExpect<'a, GameEventQueue>,
         WriteExpect<'a, MoveQueue>,
+        WriteExpect<'a, HistoryQueue>,
+        WriteExpect<'a, Animation>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (slots, mut game_state, mut occupied, mut game_event_queue, mut move_queue) = data;
+        let (
+            slots,
+            mut game_state,
+            mut occupied,
+            mut selected,
+            mut game_event_queue,
+            mut move_queue,
+            mut history_queue,
+            mut animation,
+        ) = data;
 
-        if let Some(m) = move_queue.dequeue() {
-            let prev_occupied = occupied.get(m.prev).is_some();
-            let curr_unoccupied = occupied.get(m.curr).is_none();
-            if prev_occupied && curr_unoccupied {
-                let prev_slot = slots.get(m.prev).expect("previous slot not found");
-                let curr_slot = slots.get(m.curr).expect("current slot not found");
+        // the next move waits for the jump being shown
+        if animation.is_running() {
+            return;
+        }
 
-                let dist_x = prev_slot.x as i32 - curr_slot.x as i32;
-                let dist_y = prev_slot.y as i32 - curr_slot.y as i32;
-                let slot_to_remove = match (dist_x, dist_y) {
-                    (0, 2) => game_state.board.entity_at(prev_slot.x, prev_slot.y - 1),
-                    (0, -2) => game_state.board.entity_at(prev_slot.x, prev_slot.y + 1),
-                    (2, 0) => game_state.board.entity_at(prev_slot.x - 1, prev_slot.y),
-                    (-2, 0) => game_state.board.entity_at(prev_slot.x + 1, prev_slot.y),
-                    _ => None,
-                };
+        let was_over = game_state.game.is_over();
 
-                if let Some(sr) = slot_to_remove {
-                    if let Some(_) = occupied.get(sr) {
-                        occupied.remove(m.prev);
-                        occupied.remove(sr);
-                        occupied
-                            .insert(m.curr, Occupied)
-                            .expect("failed to mark entity as occupied");
-                        game_state.move_count = game_state.move_count + 1;
-                        game_state.peg_count = game_state.peg_count - 1;
-                        game_event_queue.enqueue(GameEvent::CorrectMove);
-                    }
-                } else {
-                    game_event_queue.enqueue(GameEvent::IncorrectMove);
-                }
-            } else {
-                game_event_queue.enqueue(GameEvent::IncorrectMove);
+        if let Some(m) = move_queue.dequeue() {
+            let prev_slot = slots.get(m.prev).expect("previous slot not found");
+            let curr_slot = slots.get(m.curr).expect("current slot not found");
+
+            let jump = game_state
+                .game
+                .play((prev_slot.x, prev_slot.y), (curr_slot.x, curr_slot.y));
+            let played = jump.is_some();
+            update(
+                &mut game_state,
+                &mut occupied,
+                &mut game_event_queue,
+                &mut animation,
+                jump,
+                false,
+            );
+            if m.dropped && played {
+                animation.skip();
+                // the landing slot is selected, as after a click
+                selected.remove(m.prev);
+                selected
+                    .insert(m.curr, Selected)
+                    .expect("failed to mark entity as selected");
             }
         }
 
-        if game_state.status == GameStatus::Completed {
-            return;
+        if let Some(action) = history_queue.dequeue() {
+            let (jump, undone) = match action {
+                HistoryAction::Undo => (game_state.game.undo(), true),
+                HistoryAction::Redo => (game_state.game.redo(), false),
+            };
+            update(
+                &mut game_state,
+                &mut occupied,
+                &mut game_event_queue,
+                &mut animation,
+                jump,
+                undone,
+            );
         }
 
-        for (slot, ()) in (&slots, !&occupied).join() {
-            // check left
-            if slot.x > 1 {
-                let p1 = game_state
-                    .board
-                    .entity_to_left(slot.x, slot.y)
-                    .map(|e| occupied.get(e))
-                    .flatten();
-                let p2 = game_state
-                    .board
-                    .entity_to_left(slot.x - 1, slot.y)
-                    .map(|e| occupied.get(e))
-                    .flatten();
-                if p1.is_some() && p2.is_some() {
-                    return;
-                }
-            }
+        if !was_over && game_state.game.is_over() {
+            game_event_queue.enqueue(GameEvent::GameOver);
+        }
+    }
+}
 
-            // check above
-            if slot.y > 1 {
-                let p1 = game_state
-                    .board
-                    .entity_above(slot.x, slot.y)
-                    .map(|e| occupied.get(e))
-                    .flatten();
-                let p2 = game_state
-                    .board
-                    .entity_above(slot.x, slot.y - 1)
-                    .map(|e| occupied.get(e))
-                    .flatten();
-                if p1.is_some() && p2.is_some() {
-                    return;
-                }
+// Shows the outcome of a move, a jump taken back when `undone`
+fn update(
+    game_state: &mut GameState,
+    occupied: &mut WriteStorage<Occupied>,
+    game_event_queue: &mut GameEventQueue,
+    animation: &mut Animation,
+    jump: Option<Jump>,
+    undone: bool,
+) {
+    match jump {
+        Some(jump) => {
+            sync_jump(game_state, occupied, &jump);
+            let shape = game_state.game.shape();
+            let (from, over, to) = (
+                shape.hole(jump.from),
+                shape.hole(jump.over),
+                shape.hole(jump.to),
+            );
+            if undone {
+                animation.start(to, over, from, false);
+            } else {
+                animation.start(from, over, to, true);
             }
+            game_event_queue.enqueue(GameEvent::CorrectMove);
+        }
+        None => game_event_queue.enqueue(GameEvent::IncorrectMove),
+    }
+}
 
-            // check right
-            if slot.x < BOARD_LEN - 2 {
-                let p1 = game_state
-                    .board
-                    .entity_to_right(slot.x, slot.y)
-                    .map(|e| occupied.get(e))
-                    .flatten();
-                let p2 = game_state
-                    .board
-                    .entity_to_right(slot.x + 1, slot.y)
-                    .map(|e| occupied.get(e))
-                    .flatten();
-                if p1.is_some() && p2.is_some() {
-                    return;
-                }
-            }
+// Brings the slots of a jump in line with the position
+fn sync_jump(game_state: &GameState, occupied: &mut WriteStorage<Occupied>, jump: &Jump) {
+    let position = game_state.game.position();
+    for hole in [jump.from, jump.over, jump.to] {
+        let (x, y) = game_state.game.shape().hole(hole);
+        let entity = game_state
+            .board
+            .entity_at(x, y)
+            .expect("slot entity not found");
 
-            // check below
-            if slot.y < BOARD_LEN - 2 {
-                let p1 = game_state
-                    .board
-                    .entity_below(slot.x, slot.y)
-                    .map(|e| occupied.get(e))
-                    .flatten();
-                let p2 = game_state
-                    .board
-                    .entity_below(slot.x, slot.y + 1)
-                    .map(|e| occupied.get(e))
-                    .flatten();
-                if p1.is_some() && p2.is_some() {
-                    return;
-                }
-            }
+        if position.is_occupied(hole) {
+            occupied
+                .insert(entity, Occupied)
+                .expect("failed to mark entity as occupied");
+        } else {
+            occupied.remove(entity);
         }
-
-        // There are no possible moves if we get here.
-        game_state.status = GameStatus::Completed;
-        game_event_queue.enqueue(GameEvent::GameOver);
     }
 }
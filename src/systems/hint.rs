@@ -0,0 +1,47 @@
+use crate::resources::*;
+use pegsol_engine::background::Hint;
+use pegsol_frontend::hint::HintState;
+use specs::{Read, ReadExpect, System, Write, WriteExpect};
+
+// Points out the hint the player asked for once the solvability check of the position is done. A
+// request for a position that is no longer on the board is dropped.
+pub struct HintSystem;
+
+impl<'a> System<'a> for HintSystem {
+    type SystemData = (
+        ReadExpect<'a, GameState>,
+        WriteExpect<'a, InputEventQueue>,
+        Read<'a, Solvability>,
+        Write<'a, HintRequest>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (game_state, mut input_queue, solvability, mut request) = data;
+
+        if request
+            .position
+            .is_some_and(|p| p != game_state.game.position())
+        {
+            *request = HintRequest::default();
+            return;
+        }
+
+        if request.state != HintState::Searching || solvability.position != request.position {
+            return;
+        }
+
+        match solvability.hint {
+            // the check is still running
+            None => {}
+            Some(Hint::Jump(jump)) => {
+                input_queue.enqueue(InputEvent::Hint {
+                    from: jump.from,
+                    to: jump.to,
+                });
+                request.state = HintState::NotAsked;
+            }
+            Some(Hint::Unsolvable) => request.state = HintState::Unsolvable,
+            Some(Hint::GaveUp) => request.state = HintState::GaveUp,
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::components::*;
-use crate::constants::{PADDING_LEFT, PADDING_TOP, TILE_SIZE};
 use crate::resources::*;
+use pegsol_engine::topology::Direction;
 use specs::{Entities, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage};
 
 pub struct InputSystem;
@@ -10,8 +10,13 @@ impl<'a> System<'a> for InputSystem {
         Entities<'a>,
         ReadStorage<'a, Slot>,
         ReadExpect<'a, GameState>,
+        ReadExpect<'a, Geometry>,
         WriteExpect<'a, InputEventQueue>,
         WriteExpect<'a, MoveQueue>,
+        WriteExpect<'a, HistoryQueue>,
+        WriteExpect<'a, GameEventQueue>,
+        WriteExpect<'a, Drag>,
+        ReadStorage<'a, Occupied>,
         WriteStorage<'a, Selected>,
         WriteStorage<'a, Highlighted>,
     );
@@ -21,8 +26,13 @@ impl<'a> System<'a> for InputSystem {
             entities,
             slots,
             game_state,
+            geometry,
             mut input_queue,
             mut move_queue,
+            mut history_queue,
+            mut game_event_queue,
+            mut drag,
+            occupied,
             mut selected,
             mut highlighted,
         ) = data;
@@ -33,31 +43,79 @@ impl<'a> System<'a> for InputSystem {
 
             match event {
                 InputEvent::MouseClick { x, y } => {
-                    for (e, s) in (&*entities, &slots).join() {
-                        let sx = (s.x as f32 * TILE_SIZE) + PADDING_LEFT;
-                        let sy = (s.y as f32 * TILE_SIZE) + PADDING_TOP;
-
-                        if x >= sx && x <= sx + TILE_SIZE {
-                            if y >= sy && y <= sy + TILE_SIZE {
-                                if let Some(prev) =
-                                    (&*entities, &selected).join().map(|(en, _)| en).nth(0)
-                                {
-                                    move_queue.enqueue(Move { prev, curr: e });
-                                    selected.remove(prev);
-                                }
+                    let target = geometry.slot_at(x, y).and_then(|(sx, sy)| {
+                        let e = game_state.board.entity_at(sx, sy);
+                        e.map(|e| ((sx, sy), e))
+                    });
+                    if let Some(((sx, sy), e)) = target {
+                        // pressing on the selected peg again only picks it up
+                        if let Some(prev) = (&*entities, &selected)
+                            .join()
+                            .map(|(en, _)| en)
+                            .find(|&en| en != e)
+                        {
+                            move_queue.enqueue(Move {
+                                prev,
+                                curr: e,
+                                dropped: false,
+                            });
+                            selected.remove(prev);
+                        }
 
-                                selected
-                                    .insert(e, Selected)
-                                    .expect("failed to mark entity as selected");
+                        selected
+                            .insert(e, Selected)
+                            .expect("failed to mark entity as selected");
 
-                                return;
-                            }
+                        // a peg can also be dragged to where it should land
+                        if occupied.contains(e) {
+                            let (tile_x, tile_y) = geometry.slot_position(sx, sy);
+                            drag.peg = Some(DraggedPeg {
+                                entity: e,
+                                slot: (sx, sy),
+                                grab: (x - tile_x, y - tile_y),
+                                cursor: (x, y),
+                            });
+                        }
+                    }
+                }
+                InputEvent::MouseMotion { x, y } => {
+                    // keyboard and mouse share the cursor, which stays put off the board
+                    if let Some(e) = geometry
+                        .slot_at(x, y)
+                        .and_then(|(sx, sy)| game_state.board.entity_at(sx, sy))
+                        .filter(|&e| e != entity)
+                    {
+                        highlighted.remove(entity);
+                        highlighted
+                            .insert(e, Highlighted)
+                            .expect("failed to mark entity as highlighted");
+                    }
+                }
+                InputEvent::MouseRelease { x, y } => {
+                    if let Some(peg) = drag.peg.take() {
+                        let target = geometry
+                            .slot_at(x, y)
+                            .and_then(|(sx, sy)| game_state.board.entity_at(sx, sy));
+                        match target {
+                            // dropped where it was picked up, it stays selected as after a click
+                            Some(e) if e == peg.entity => (),
+                            // the peg stays selected unless the drop is played
+                            Some(e) => move_queue.enqueue(Move {
+                                prev: peg.entity,
+                                curr: e,
+                                dropped: true,
+                            }),
+                            None => game_event_queue.enqueue(GameEvent::IncorrectMove),
                         }
                     }
                 }
                 InputEvent::Enter => {
                     if let Some(prev) = (&*entities, &selected).join().map(|(e, _)| e).nth(0) {
-                        move_queue.enqueue(Move { prev, curr: entity });
+                        move_queue.enqueue(Move {
+                            prev,
+                            curr: entity,
+                            dropped: false,
+                        });
                         selected.remove(prev);
                     }
 
@@ -65,32 +123,47 @@ impl<'a> System<'a> for InputSystem {
                         .insert(entity, Selected)
                         .expect("failed to mark entity as selected");
                 }
-                InputEvent::Up => {
-                    if let Some(e) = game_state.board.entity_above(slot.x, slot.y) {
-                        highlighted
-                            .insert(e, Highlighted)
-                            .expect("failed to mark entity as highlighted");
-                        highlighted.remove(entity);
-                    }
+                InputEvent::Undo => {
+                    history_queue.enqueue(HistoryAction::Undo);
+                    selected.clear();
                 }
-                InputEvent::Down => {
-                    if let Some(e) = game_state.board.entity_below(slot.x, slot.y) {
-                        highlighted
-                            .insert(e, Highlighted)
-                            .expect("failed to mark entity as highlighted");
-                        highlighted.remove(entity);
-                    }
+                InputEvent::Redo => {
+                    history_queue.enqueue(HistoryAction::Redo);
+                    selected.clear();
                 }
-                InputEvent::Left => {
-                    if let Some(e) = game_state.board.entity_to_left(slot.x, slot.y) {
+                InputEvent::Deselect => selected.clear(),
+                InputEvent::Hint { from, to } => {
+                    let peg = game_state.board.entity_at(from.0, from.1);
+                    let landing = game_state.board.entity_at(to.0, to.1);
+                    if let (Some(peg), Some(landing)) = (peg, landing) {
+                        selected.clear();
+                        selected
+                            .insert(peg, Selected)
+                            .expect("failed to mark entity as selected");
+                        highlighted.remove(entity);
                         highlighted
-                            .insert(e, Highlighted)
+                            .insert(landing, Highlighted)
                             .expect("failed to mark entity as highlighted");
-                        highlighted.remove(entity);
                     }
                 }
-                InputEvent::Right => {
-                    if let Some(e) = game_state.board.entity_to_right(slot.x, slot.y) {
+                _ => {
+                    let direction = match event {
+                        InputEvent::Up => Direction::Up,
+                        InputEvent::Down => Direction::Down,
+                        InputEvent::Left => Direction::Left,
+                        InputEvent::Right => Direction::Right,
+                        InputEvent::UpLeft => Direction::UpLeft,
+                        InputEvent::UpRight => Direction::UpRight,
+                        InputEvent::DownLeft => Direction::DownLeft,
+                        _ => Direction::DownRight,
+                    };
+
+                    if let Some(e) = game_state
+                        .game
+                        .shape()
+                        .slot_towards(slot.x, slot.y, direction)
+                        .and_then(|(x, y)| game_state.board.entity_at(x, y))
+                    {
                         highlighted
                             .insert(e, Highlighted)
                             .expect("failed to mark entity as highlighted");
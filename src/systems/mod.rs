@@ -1,4 +1,9 @@
+pub mod animation;
 pub mod gameplay;
+pub mod hint;
 pub mod input;
+pub mod move_hints;
 pub mod rendering;
+pub mod replay;
+pub mod solvability;
 pub mod sound;
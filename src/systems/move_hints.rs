@@ -0,0 +1,45 @@
+use crate::components::*;
+use crate::resources::*;
+use specs::{Join, ReadExpect, ReadStorage, System, WriteStorage};
+
+// Marks where the selected peg can jump to, and which pegs can jump at all. The marks come from
+// the legal jumps of the game, the same ones it accepts when a move is played.
+pub struct MoveHintSystem;
+
+impl<'a> System<'a> for MoveHintSystem {
+    type SystemData = (
+        ReadStorage<'a, Slot>,
+        ReadStorage<'a, Selected>,
+        ReadExpect<'a, GameState>,
+        ReadExpect<'a, MoveHints>,
+        WriteStorage<'a, Destination>,
+        WriteStorage<'a, Movable>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (slots, selected, game_state, move_hints, mut destinations, mut movable) = data;
+
+        destinations.clear();
+        movable.clear();
+
+        let entity_at = |(x, y): (usize, usize)| game_state.board.entity_at(x, y);
+
+        for (slot, _) in (&slots, &selected).join() {
+            let landings = game_state.game.destinations((slot.x, slot.y));
+            for entity in landings.into_iter().filter_map(entity_at) {
+                destinations
+                    .insert(entity, Destination)
+                    .expect("failed to mark entity as destination");
+            }
+        }
+
+        if move_hints.movable {
+            let pegs = game_state.game.movable_pegs();
+            for entity in pegs.into_iter().filter_map(entity_at) {
+                movable
+                    .insert(entity, Movable)
+                    .expect("failed to mark entity as movable");
+            }
+        }
+    }
+}
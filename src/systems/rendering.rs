@@ -1,11 +1,14 @@
 use crate::components::*;
-use crate::constants::{PADDING_LEFT, PADDING_TOP, TILE_SIZE};
 use crate::resources::*;
 use ggez::graphics::{
-    spritebatch::SpriteBatch, Align, Color, DrawParam, Scale, Text, TextFragment,
+    spritebatch::SpriteBatch, Align, Color, DrawMode, DrawParam, MeshBuilder, Scale, Text,
+    TextFragment,
 };
 use ggez::{graphics, nalgebra as na, timer, Context};
 use itertools::Itertools;
+use pegsol_engine::game::GameStatus;
+use pegsol_engine::notation;
+use pegsol_frontend::hint::HintState;
 use specs::{Join, Read, ReadExpect, ReadStorage, System};
 
 pub struct RenderingSystem<'a> {
@@ -13,7 +16,92 @@ pub struct RenderingSystem<'a> {
 }
 
 impl<'a> RenderingSystem<'a> {
-    fn draw_banner(&mut self, asset_store: &AssetStore, moves: u8, pegs: u8, game_over: bool) {
+    // Marks the slots the selected peg can jump to with a dot, and pegs that can jump with a ring
+    fn draw_move_hints(
+        &mut self,
+        geometry: &Geometry,
+        destinations: Vec<(usize, usize)>,
+        movable: Vec<(usize, usize)>,
+    ) {
+        if destinations.is_empty() && movable.is_empty() {
+            return;
+        }
+
+        let radius = geometry.tile_size / 2.0;
+        let centre = |(x, y): (usize, usize)| {
+            let (x, y) = geometry.slot_position(x, y);
+            na::Point2::new(x + radius, y + radius)
+        };
+
+        let mut mesh = MeshBuilder::new();
+        for slot in destinations {
+            mesh.circle(
+                DrawMode::fill(),
+                centre(slot),
+                radius * 0.3,
+                0.5,
+                Color::new(0.2, 0.9, 0.3, 0.8),
+            );
+        }
+        for slot in movable {
+            mesh.circle(
+                DrawMode::stroke(radius * 0.08),
+                centre(slot),
+                radius * 0.85,
+                0.5,
+                Color::new(0.3, 0.7, 1.0, 0.9),
+            );
+        }
+
+        let mesh = mesh
+            .build(self.context)
+            .expect("failed to build move hints");
+        graphics::draw(self.context, &mesh, DrawParam::new()).expect("failed to render move hints");
+    }
+
+    // Draws the pegs of the jump being shown over their empty slots
+    fn draw_tween(&mut self, asset_store: &AssetStore, geometry: &Geometry, tween: &Tween) {
+        let peg = asset_store.peg();
+        let scale = geometry.scale();
+
+        // the peg jumped over shrinks and fades out
+        let size = tween.captured();
+        let (x, y) = geometry.slot_position(tween.over.0, tween.over.1);
+        let inset = geometry.tile_size * (1.0 - size) / 2.0;
+        graphics::draw(
+            self.context,
+            &peg,
+            DrawParam::new()
+                .dest(na::Point2::new(x + inset, y + inset))
+                .scale(na::Vector2::new(scale * size, scale * size))
+                .color(Color::new(1.0, 1.0, 1.0, size)),
+        )
+        .expect("failed to render captured peg");
+
+        // while the jumping peg follows an arc over it
+        let t = tween.progress();
+        let (from_x, from_y) = geometry.slot_position(tween.from.0, tween.from.1);
+        let (to_x, to_y) = geometry.slot_position(tween.to.0, tween.to.1);
+        let x = from_x + (to_x - from_x) * t;
+        let y = from_y + (to_y - from_y) * t - tween.lift() * geometry.tile_size;
+        graphics::draw(
+            self.context,
+            &peg,
+            DrawParam::new()
+                .dest(na::Point2::new(x, y))
+                .scale(na::Vector2::new(scale, scale)),
+        )
+        .expect("failed to render jumping peg");
+    }
+
+    fn draw_banner(
+        &mut self,
+        asset_store: &AssetStore,
+        game_state: &GameState,
+        solvability: &SolvabilityStatus,
+        hint: &HintRequest,
+        replay: Option<(&Replay, String)>,
+    ) {
         graphics::draw(
             self.context,
             &asset_store.image(ImageType::Header),
@@ -22,7 +110,7 @@ impl<'a> RenderingSystem<'a> {
         .expect("failed to render header");
 
         let mut moves_txt = Text::new(
-            TextFragment::new(format!("Moves\n{:05}", moves))
+            TextFragment::new(format!("Moves\n{:05}", game_state.game.move_count()))
                 .font(asset_store.font())
                 .scale(Scale::uniform(36.0)),
         );
@@ -34,9 +122,26 @@ impl<'a> RenderingSystem<'a> {
             Some(Color::new(1.0, 1.0, 1.0, 1.0)),
         );
 
-        if game_over {
+        let status_txt = match game_state.game.status() {
+            GameStatus::OnGoing => match hint.state {
+                HintState::NotAsked => None,
+                HintState::Searching => Some(("SEARCHING...", Color::new(0.4, 0.4, 0.4, 1.0))),
+                HintState::Unsolvable => Some(("NO SOLUTION", Color::new(1.0, 0.6, 0.0, 1.0))),
+                HintState::GaveUp => Some(("NO HINT FOUND", Color::new(1.0, 0.6, 0.0, 1.0))),
+            },
+            GameStatus::Completed => Some(("GAME OVER", Color::new(1.0, 0.0, 0.0, 1.0))),
+            GameStatus::Solved => Some(("SOLVED", Color::new(0.0, 1.0, 0.0, 1.0))),
+        };
+
+        // the badge fills the status area while the game is on
+        if status_txt.is_none() && replay.is_none() {
+            self.draw_solvability_badge(asset_store, solvability);
+        }
+
+        // a replay shows the status alongside the jumps instead
+        if let Some((txt, color)) = status_txt.filter(|_| replay.is_none()) {
             let mut game_over_txt = Text::new(
-                TextFragment::new("GAME OVER")
+                TextFragment::new(txt)
                     .font(asset_store.font())
                     .scale(Scale::uniform(48.0)),
             );
@@ -45,12 +150,32 @@ impl<'a> RenderingSystem<'a> {
                 self.context,
                 game_over_txt.set_bounds([400.0, 100.0], Align::Center),
                 na::Point2::new(150.0, 20.0),
-                Some(Color::new(1.0, 0.0, 0.0, 1.0)),
+                Some(color),
             );
         }
 
+        // with chained jumps the move count no longer matches the number of jumps
+        if game_state.game.rules().chain_jumps && replay.is_none() {
+            let mut jumps_txt = Text::new(
+                TextFragment::new(format!("Jumps {}", game_state.game.jump_count()))
+                    .font(asset_store.font())
+                    .scale(Scale::uniform(24.0)),
+            );
+
+            graphics::queue_text(
+                self.context,
+                jumps_txt.set_bounds([400.0, 30.0], Align::Center),
+                na::Point2::new(150.0, 70.0),
+                Some(Color::new(1.0, 1.0, 1.0, 1.0)),
+            );
+        }
+
+        if let Some((replay, jump)) = replay {
+            self.queue_replay_text(asset_store, game_state, replay, jump);
+        }
+
         let mut pegs_txt = Text::new(
-            TextFragment::new(format!("Pegs\n{:04}", pegs))
+            TextFragment::new(format!("Pegs\n{:04}", game_state.game.peg_count()))
                 .font(asset_store.font())
                 .scale(Scale::uniform(36.0)),
         );
@@ -71,6 +196,108 @@ impl<'a> RenderingSystem<'a> {
         .expect("expected drawing queued text");
     }
 
+    fn draw_solvability_badge(&mut self, asset_store: &AssetStore, status: &SolvabilityStatus) {
+        let (txt, color) = match status {
+            SolvabilityStatus::Unknown => return,
+            SolvabilityStatus::Checking => ("CHECKING...", Color::new(0.4, 0.4, 0.4, 1.0)),
+            SolvabilityStatus::Solvable { centre: true } => {
+                ("SOLVABLE TO CENTRE", Color::new(0.1, 0.5, 0.2, 1.0))
+            }
+            SolvabilityStatus::Solvable { centre: false } => {
+                ("SOLVABLE", Color::new(0.1, 0.5, 0.2, 1.0))
+            }
+            SolvabilityStatus::Unsolvable { .. } => {
+                ("NOT SOLVABLE", Color::new(0.7, 0.1, 0.1, 1.0))
+            }
+        };
+
+        // explain why the position is lost above the badge
+        if let SolvabilityStatus::Unsolvable {
+            reason: Some(reason),
+        } = status
+        {
+            let mut reason_txt = Text::new(
+                TextFragment::new(reason.as_str())
+                    .font(asset_store.font())
+                    .scale(Scale::uniform(16.0)),
+            );
+
+            graphics::queue_text(
+                self.context,
+                reason_txt.set_bounds([400.0, 24.0], Align::Center),
+                na::Point2::new(150.0, 8.0),
+                Some(Color::new(1.0, 1.0, 1.0, 1.0)),
+            );
+        }
+
+        let bounds = graphics::Rect::new(220.0, 32.0, 260.0, 36.0);
+        let badge =
+            graphics::Mesh::new_rectangle(self.context, graphics::DrawMode::fill(), bounds, color)
+                .expect("failed to create badge");
+        graphics::draw(self.context, &badge, DrawParam::default()).expect("failed to render badge");
+
+        let mut badge_txt = Text::new(
+            TextFragment::new(txt)
+                .font(asset_store.font())
+                .scale(Scale::uniform(22.0)),
+        );
+
+        graphics::queue_text(
+            self.context,
+            badge_txt.set_bounds([bounds.w, bounds.h], Align::Center),
+            na::Point2::new(bounds.x, bounds.y + 7.0),
+            Some(Color::new(1.0, 1.0, 1.0, 1.0)),
+        );
+    }
+
+    // Replaces the status text while a replay is being watched
+    fn queue_replay_text(
+        &mut self,
+        asset_store: &AssetStore,
+        game_state: &GameState,
+        replay: &Replay,
+        jump: String,
+    ) {
+        let playback = match game_state.game.status() {
+            GameStatus::Solved => "SOLVED".to_string(),
+            GameStatus::Completed => "GAME OVER".to_string(),
+            GameStatus::OnGoing if replay.playing => {
+                format!("Playing, {:.2}s per jump", replay.interval.as_secs_f32())
+            }
+            GameStatus::OnGoing => "Paused".to_string(),
+        };
+
+        let lines = [
+            (
+                format!(
+                    "Move {} of {}",
+                    game_state.game.move_count(),
+                    replay.move_count
+                ),
+                28.0,
+            ),
+            (jump, 24.0),
+            (playback, 20.0),
+        ];
+
+        let mut y = 12.0;
+        for (txt, size) in lines.iter() {
+            let mut replay_txt = Text::new(
+                TextFragment::new(txt.as_str())
+                    .font(asset_store.font())
+                    .scale(Scale::uniform(*size)),
+            );
+
+            graphics::queue_text(
+                self.context,
+                replay_txt.set_bounds([400.0, 40.0], Align::Center),
+                na::Point2::new(150.0, y),
+                Some(Color::new(1.0, 1.0, 1.0, 1.0)),
+            );
+            y += size + 6.0;
+        }
+    }
+
     fn draw_fps(&mut self, fps: f64) {
         let mut fps_txt =
             Text::new(TextFragment::new(format!("FPS: {:.0}", fps)).scale(Scale::uniform(14.0)));
@@ -98,12 +325,35 @@ impl<'a> System<'a> for RenderingSystem<'a> {
         ReadStorage<'a, Occupied>,
         ReadStorage<'a, Selected>,
         ReadStorage<'a, Highlighted>,
+        ReadStorage<'a, Destination>,
+        ReadStorage<'a, Movable>,
         ReadExpect<'a, GameState>,
+        ReadExpect<'a, Geometry>,
         Read<'a, AssetStore>,
+        Read<'a, Solvability>,
+        Read<'a, HintRequest>,
+        Option<Read<'a, Replay>>,
+        ReadExpect<'a, Animation>,
+        ReadExpect<'a, Drag>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (slots, occupied, selected, highlighted, game_state, asset_store) = data;
+        let (
+            slots,
+            occupied,
+            selected,
+            highlighted,
+            destinations,
+            movable,
+            game_state,
+            geometry,
+            asset_store,
+            solvability,
+            hint,
+            replay,
+            animation,
+            drag,
+        ) = data;
 
         graphics::clear(self.context, graphics::Color::new(0.0, 0.0, 0.0, 1.0));
 
@@ -117,20 +367,29 @@ impl<'a> System<'a> for RenderingSystem<'a> {
         )
             .join()
         {
-            let sprite_type = match (occ, sel, hi) {
-                (Some(_), Some(_), Some(_)) => ImageType::OccupiedSelectedHighlighted,
-                (Some(_), Some(_), None) => ImageType::OccupiedSelected,
-                (Some(_), None, Some(_)) => ImageType::OccupiedHighlighted,
-                (Some(_), None, None) => ImageType::OccupiedNormal,
-                (None, Some(_), Some(_)) => ImageType::UnoccupiedSelectedHighlighted,
-                (None, Some(_), None) => ImageType::UnoccupiedSelected,
-                (None, None, Some(_)) => ImageType::UnoccupiedHighlighted,
-                _ => ImageType::UnoccupiedNormal,
-            };
+            // the pegs of a jump being shown, and a dragged peg, are drawn on their own
+            let jumping = animation
+                .tween
+                .as_ref()
+                .is_some_and(|t| [t.from, t.over, t.to].contains(&(slot.x, slot.y)));
+            let dragged = drag
+                .peg
+                .as_ref()
+                .is_some_and(|p| p.slot == (slot.x, slot.y));
+            let sprite_type = ImageType::slot(
+                occ.is_some() && !jumping && !dragged,
+                sel.is_some(),
+                hi.is_some(),
+            );
 
-            let x = (slot.x as f32 * TILE_SIZE) + PADDING_LEFT;
-            let y = (slot.y as f32 * TILE_SIZE) + PADDING_TOP;
-            sprite_groups.push((sprite_type, DrawParam::new().dest(na::Point2::new(x, y))));
+            let (x, y) = geometry.slot_position(slot.x, slot.y);
+            let scale = geometry.scale();
+            sprite_groups.push((
+                sprite_type,
+                DrawParam::new()
+                    .dest(na::Point2::new(x, y))
+                    .scale(na::Vector2::new(scale, scale)),
+            ));
         }
 
         sprite_groups
@@ -148,11 +407,55 @@ impl<'a> System<'a> for RenderingSystem<'a> {
                     .expect("failed to render");
             });
 
+        // hints would get in the way of a jump being shown
+        match &animation.tween {
+            Some(tween) => self.draw_tween(&asset_store, &geometry, tween),
+            None => {
+                let destinations = (&slots, &destinations).join().map(|(s, _)| (s.x, s.y));
+                let movable = (&slots, &movable).join().map(|(s, _)| (s.x, s.y));
+                self.draw_move_hints(&geometry, destinations.collect(), movable.collect());
+            }
+        }
+
+        // the dragged peg stays where it was grabbed under the cursor
+        if let Some(peg) = &drag.peg {
+            let scale = geometry.scale();
+            let (x, y) = (peg.cursor.0 - peg.grab.0, peg.cursor.1 - peg.grab.1);
+            graphics::draw(
+                self.context,
+                &asset_store.peg(),
+                DrawParam::new()
+                    .dest(na::Point2::new(x, y))
+                    .scale(na::Vector2::new(scale, scale)),
+            )
+            .expect("failed to render dragged peg");
+        }
+
+        // describe the jump that led to the position being shown
+        let replay = replay.as_deref().map(|replay| {
+            let name = |hole| {
+                let (x, y) = game_state.game.shape().hole(hole);
+                notation::slot_name(x, y)
+            };
+            let jump = match game_state.game.history().last() {
+                Some(entry) => format!(
+                    "{}-{} takes {}",
+                    name(entry.jump.from),
+                    name(entry.jump.to),
+                    name(entry.jump.over)
+                ),
+                None => "Starting position".to_string(),
+            };
+
+            (replay, jump)
+        });
+
         self.draw_banner(
             &asset_store,
-            game_state.move_count,
-            game_state.peg_count,
-            game_state.status == GameStatus::Completed,
+            &game_state,
+            &solvability.status,
+            &hint,
+            replay,
         );
 
         self.draw_fps(timer::fps(self.context));
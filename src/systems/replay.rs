@@ -0,0 +1,69 @@
+use crate::resources::*;
+use specs::{ReadExpect, System, Write, WriteExpect};
+use std::time::Duration;
+
+// Steps through a replay by undoing and redoing the recorded jumps
+pub struct ReplaySystem {
+    // Time since the previous frame
+    pub delta: Duration,
+}
+
+impl<'a> System<'a> for ReplaySystem {
+    type SystemData = (
+        Option<Write<'a, Replay>>,
+        WriteExpect<'a, ReplayQueue>,
+        WriteExpect<'a, HistoryQueue>,
+        ReadExpect<'a, GameState>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (replay, mut replay_queue, mut history_queue, game_state) = data;
+        let mut replay = match replay {
+            Some(r) => r,
+            None => return,
+        };
+
+        // wait for the previous step to be played before taking the next one
+        if !history_queue.is_empty() {
+            return;
+        }
+
+        let position = game_state.game.history().entries().len();
+        let at_end = position == replay.jump_count;
+
+        if let Some(action) = replay_queue.dequeue() {
+            match action {
+                ReplayAction::Forward if !at_end => {
+                    replay.playing = false;
+                    history_queue.enqueue(HistoryAction::Redo);
+                }
+                ReplayAction::Back if position > 0 => {
+                    replay.playing = false;
+                    history_queue.enqueue(HistoryAction::Undo);
+                }
+                ReplayAction::TogglePlay => {
+                    replay.playing = !replay.playing && !at_end;
+                    replay.elapsed = Duration::default();
+                }
+                ReplayAction::Faster => replay.faster(),
+                ReplayAction::Slower => replay.slower(),
+                _ => (),
+            }
+
+            return;
+        }
+
+        if !replay.playing {
+            return;
+        }
+
+        replay.elapsed += self.delta;
+        let interval = replay.interval;
+        if replay.elapsed >= interval {
+            replay.elapsed -= interval;
+            history_queue.enqueue(HistoryAction::Redo);
+            // stop once the last jump has been played
+            replay.playing = position + 1 < replay.jump_count;
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use crate::resources::*;
+use pegsol_engine::background::{BackgroundSearch, Hint};
+use pegsol_engine::level::Goal;
+use pegsol_engine::position::{Position, Shape};
+use pegsol_engine::solver::{self, Interrupted, Solution, Solver, SEARCH_BUDGET};
+use specs::{ReadExpect, System, Write};
+use std::sync::atomic::AtomicBool;
+
+// Checks in the background whether the position can still be solved whenever it changes, and
+// finds the first jump toward the goal of the game along the way for the hint
+pub struct SolvabilitySystem;
+
+impl<'a> System<'a> for SolvabilitySystem {
+    type SystemData = (ReadExpect<'a, GameState>, Write<'a, Solvability>);
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (game_state, mut solvability) = data;
+
+        let position = game_state.game.position();
+        if solvability.position != Some(position) {
+            // replacing the search cancels the one for the previous position
+            solvability.search = Some(spawn_search(
+                game_state.game.shape().clone(),
+                game_state.game.goal(),
+                position,
+            ));
+            solvability.status = SolvabilityStatus::Checking;
+            solvability.hint = None;
+            solvability.position = Some(position);
+            return;
+        }
+
+        let result = solvability
+            .search
+            .as_ref()
+            .and_then(|search| search.take_result());
+        if let Some((status, hint)) = result {
+            solvability.status = status;
+            solvability.hint = Some(hint);
+            solvability.search = None;
+        }
+    }
+}
+
+fn spawn_search(shape: Shape, goal: Goal, position: Position) -> BackgroundSearch<Check> {
+    BackgroundSearch::spawn(move |cancel| match check(&shape, goal, position, cancel) {
+        Ok(check) => Some(check),
+        // a search too large to finish in the background says nothing either way
+        Err(Interrupted::OutOfBudget) => Some((SolvabilityStatus::Unknown, Hint::GaveUp)),
+        // a cancelled search has nothing to report
+        Err(Interrupted::Cancelled) => None,
+    })
+}
+
+fn check(
+    shape: &Shape,
+    goal: Goal,
+    position: Position,
+    cancel: &AtomicBool,
+) -> Result<Check, Interrupted> {
+    let solve = |goal| -> Result<Option<Solution>, Interrupted> {
+        match Solver::new(shape.clone(), goal, position) {
+            Some(solver) => solver.solve_cancellable(cancel, SEARCH_BUDGET),
+            // the goal is not a hole of the board
+            None => Ok(None),
+        }
+    };
+
+    // a proof is quicker than a search and tells the player why
+    let proof =
+        Solver::new(shape.clone(), Goal::SinglePeg, position).and_then(|solver| solver.explain());
+    if let Some(reason) = proof {
+        let status = SolvabilityStatus::Unsolvable {
+            reason: Some(reason.to_string()),
+        };
+        return Ok((status, Hint::Unsolvable));
+    }
+
+    let single = match solve(Goal::SinglePeg)? {
+        Some(solution) => solution,
+        None => {
+            let status = SolvabilityStatus::Unsolvable { reason: None };
+            return Ok((status, Hint::Unsolvable));
+        }
+    };
+
+    let centre = solver::centre(shape).map(|(x, y)| {
+        let centre_goal = Goal::SinglePegAt { x, y };
+        (centre_goal, solve(centre_goal))
+    });
+    if let Some((_, Err(Interrupted::Cancelled))) = centre {
+        return Err(Interrupted::Cancelled);
+    }
+    // the position is known to be solvable even if the search for the centre runs out of budget
+    let status = SolvabilityStatus::Solvable {
+        centre: matches!(centre, Some((_, Ok(Some(_))))),
+    };
+
+    // the goal of the game is usually one of the goals searched for already
+    let search = match centre {
+        _ if goal == Goal::SinglePeg => Ok(Some(single)),
+        Some((centre_goal, centre)) if centre_goal == goal => centre,
+        _ => solve(goal),
+    };
+    let hint = Hint::from_search(search).ok_or(Interrupted::Cancelled)?;
+
+    Ok((status, hint))
+}
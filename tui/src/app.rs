@@ -0,0 +1,141 @@
+use pegsol_engine::background::{self, BackgroundSearch, Hint};
+use pegsol_engine::game::Game;
+use pegsol_engine::topology::Direction;
+use pegsol_frontend::catalogue::Catalogue;
+use pegsol_frontend::hint::HintState;
+
+// Actions of the player, the same as those of the window front-end
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Move(Direction),
+    Enter,
+    Click { x: usize, y: usize },
+    Undo,
+    Redo,
+    Restart,
+    NewGame,
+    Hint,
+    Quit,
+}
+
+pub struct App {
+    catalogue: Catalogue,
+    pub game: Game,
+    // Slot under the cursor
+    pub cursor: (usize, usize),
+    // Peg picked to jump with
+    pub selected: Option<(usize, usize)>,
+    // Search for a hint from the current position, while it runs
+    hint: Option<BackgroundSearch<Hint>>,
+    pub hint_state: HintState,
+    pub quit: bool,
+}
+
+impl App {
+    pub fn new(catalogue: Catalogue) -> Self {
+        let game = catalogue.start_game();
+        Self {
+            cursor: game.first_empty_slot(),
+            catalogue,
+            game,
+            selected: None,
+            hint: None,
+            hint_state: HintState::default(),
+            quit: false,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        self.catalogue.title()
+    }
+
+    pub fn handle(&mut self, action: Action) {
+        match action {
+            Action::Move(direction) => {
+                let (x, y) = self.cursor;
+                if let Some(slot) = self.game.shape().slot_towards(x, y, direction) {
+                    self.cursor = slot;
+                }
+            }
+            Action::Enter => self.select(self.cursor),
+            Action::Click { x, y } => {
+                if self.game.shape().hole_at(x, y).is_some() {
+                    self.select((x, y));
+                }
+            }
+            Action::Undo => {
+                self.selected = None;
+                if self.game.undo().is_some() {
+                    self.position_changed();
+                }
+            }
+            Action::Redo => {
+                self.selected = None;
+                if self.game.redo().is_some() {
+                    self.position_changed();
+                }
+            }
+            Action::Restart => self.restart(),
+            Action::NewGame => {
+                self.catalogue.next();
+                self.restart();
+            }
+            Action::Hint => self.show_hint(),
+            Action::Quit => self.quit = true,
+        }
+    }
+
+    // Picks a peg, or jumps with the peg picked before onto the slot
+    fn select(&mut self, slot: (usize, usize)) {
+        if let Some(prev) = self.selected.take() {
+            if self.game.play(prev, slot).is_some() {
+                self.position_changed();
+            }
+        }
+
+        self.selected = Some(slot);
+    }
+
+    fn restart(&mut self) {
+        self.game = self.catalogue.start_game();
+        // the cursor starts on the first empty slot
+        self.cursor = self.game.first_empty_slot();
+        self.selected = None;
+        self.position_changed();
+    }
+
+    // Forgets what was found out about the previous position. Dropping the hint search cancels it.
+    fn position_changed(&mut self) {
+        self.hint = None;
+        self.hint_state = HintState::NotAsked;
+    }
+
+    // Looks for the first jump of a solution in the background, see `poll_hint`
+    fn show_hint(&mut self) {
+        if self.game.is_over() || !self.hint_state.can_ask() {
+            return;
+        }
+
+        self.hint = Some(background::hint(&self.game));
+        self.hint_state = HintState::Searching;
+    }
+
+    // Selects the peg of the first jump of the hint and puts the cursor on its landing slot, once
+    // the search has finished. Returns whether anything changed.
+    pub fn poll_hint(&mut self) -> bool {
+        let result = self.hint.as_ref().and_then(|search| search.take_result());
+        match result {
+            None => return false,
+            Some(Hint::Jump(jump)) => {
+                self.selected = Some(jump.from);
+                self.cursor = jump.to;
+                self.hint_state = HintState::NotAsked;
+            }
+            Some(Hint::Unsolvable) => self.hint_state = HintState::Unsolvable,
+            Some(Hint::GaveUp) => self.hint_state = HintState::GaveUp,
+        }
+
+        self.hint = None;
+        true
+    }
+}
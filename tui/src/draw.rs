@@ -0,0 +1,109 @@
+use crate::app::App;
+use crossterm::style::{Color, Print, ResetColor, SetForegroundColor};
+use crossterm::terminal::{Clear, ClearType};
+use crossterm::{cursor::MoveTo, queue};
+use pegsol_engine::game::GameStatus;
+use pegsol_frontend::hint::HintState;
+use std::io::{self, Write};
+
+// Terminal placement of the board. Every slot takes a cell of a few columns, so that the cursor
+// can be drawn around it, and the rows of triangular boards are shifted by half a cell.
+const BOARD_TOP: u16 = 4;
+const BOARD_LEFT: u16 = 2;
+const CELL_WIDTH: u16 = 4;
+
+const PEG: char = '●';
+const HOLE: char = '○';
+
+pub fn draw(out: &mut impl Write, app: &App) -> io::Result<()> {
+    queue!(out, Clear(ClearType::All), MoveTo(0, 0), Print(app.title()))?;
+
+    // counters, like the banner of the window
+    let game = &app.game;
+    let mut counters = format!(
+        "Moves {:05}   Pegs {:04}",
+        game.move_count(),
+        game.peg_count()
+    );
+    if game.rules().chain_jumps {
+        counters += &format!("   Jumps {}", game.jump_count());
+    }
+    queue!(out, MoveTo(0, 1), Print(counters))?;
+
+    let status = match game.status() {
+        GameStatus::OnGoing => match app.hint_state {
+            HintState::NotAsked => None,
+            HintState::Searching => Some(("SEARCHING...", Color::DarkGrey)),
+            HintState::Unsolvable => Some(("NO SOLUTION", Color::DarkYellow)),
+            HintState::GaveUp => Some(("NO HINT FOUND", Color::DarkYellow)),
+        },
+        GameStatus::Completed => Some(("GAME OVER", Color::Red)),
+        GameStatus::Solved => Some(("SOLVED", Color::Green)),
+    };
+    if let Some((text, color)) = status {
+        queue!(
+            out,
+            MoveTo(0, 2),
+            SetForegroundColor(color),
+            Print(text),
+            ResetColor
+        )?;
+    }
+
+    let shape = game.shape();
+    let position = game.position();
+    for (hole, (x, y)) in shape.holes().iter().copied().enumerate() {
+        let (column, row) = cell_position(app, x, y);
+        let symbol = if position.is_occupied(hole) {
+            PEG
+        } else {
+            HOLE
+        };
+        let (left, right) = if app.cursor == (x, y) {
+            ('[', ']')
+        } else {
+            (' ', ' ')
+        };
+
+        queue!(out, MoveTo(column, row), Print(left))?;
+        if app.selected == Some((x, y)) {
+            queue!(out, SetForegroundColor(Color::Yellow))?;
+        }
+        queue!(out, Print(symbol), ResetColor, Print(right))?;
+    }
+
+    let help =
+        "arrows/WASD move  QEZC diagonal  Enter select  U undo  R redo  N next  H hint  Esc quit";
+    queue!(
+        out,
+        MoveTo(0, BOARD_TOP + shape.height() as u16 + 1),
+        Print(help)
+    )?;
+
+    out.flush()
+}
+
+// Slot under the given terminal cell
+pub fn slot_at(app: &App, column: u16, row: u16) -> Option<(usize, usize)> {
+    let shape = app.game.shape();
+    let y = row.checked_sub(BOARD_TOP)? as usize;
+    if y >= shape.height() {
+        return None;
+    }
+
+    let row_left = BOARD_LEFT + row_offset(app, y);
+    let x = (column.checked_sub(row_left)? / CELL_WIDTH) as usize;
+    Some((x, y))
+}
+
+// Terminal cell of the left side of a slot
+fn cell_position(app: &App, x: usize, y: usize) -> (u16, u16) {
+    let column = BOARD_LEFT + row_offset(app, y) + x as u16 * CELL_WIDTH;
+    (column, BOARD_TOP + y as u16)
+}
+
+fn row_offset(app: &App, y: usize) -> u16 {
+    let shape = app.game.shape();
+    let offset = shape.topology().row_offset(y, shape.height());
+    (offset * CELL_WIDTH as f32) as u16
+}
@@ -0,0 +1,136 @@
+use crate::app::{Action, App};
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+    KeyModifiers, MouseButton, MouseEventKind,
+};
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{cursor, execute};
+use pegsol_engine::level::Level;
+use pegsol_engine::options::{BoardChoice, BoardOptions};
+use pegsol_engine::topology::Direction;
+use pegsol_frontend::args::{option_value, parse_board_option, split_option, UsageError};
+use pegsol_frontend::catalogue::{load_catalogue, Catalogue};
+use std::io::{self, Write};
+use std::time::Duration;
+use std::{env, process};
+
+mod app;
+mod draw;
+
+// How often a running hint search is checked on while the player does nothing
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+const USAGE: &str = "\
+Usage: pegsol-tui [--variant <NAME>] [--start <SLOT>] [--diagonal] [--chains]
+
+The board options are the same as those of `pegsol play`.
+";
+
+// Terminal front-end, for playing over SSH
+fn main() {
+    let mut levels = load_catalogue();
+    let board = parse_args(&mut levels).unwrap_or_else(|e| usage_error(&e.0));
+
+    let mut app = App::new(Catalogue::new(levels, board));
+    if let Err(e) = run(&mut app) {
+        eprintln!("terminal error: {}", e);
+        process::exit(1);
+    }
+}
+
+// Picks the board from the command line
+fn parse_args(levels: &mut [Level]) -> Result<BoardChoice, UsageError> {
+    let mut board = BoardOptions::default();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let (option, inline_value) = split_option(&arg);
+        if option == "--help" {
+            print!("{}", USAGE);
+            process::exit(0);
+        }
+
+        let value = || option_value(option, inline_value, &mut args);
+        if !parse_board_option(&mut board, option, value)? {
+            return Err(UsageError(format!("unknown option `{}`", arg)));
+        }
+    }
+
+    Ok(board.pick_board(levels)?)
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    process::exit(2);
+}
+
+fn run(app: &mut App) -> io::Result<()> {
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen, EnableMouseCapture, cursor::Hide)?;
+
+    let result = event_loop(&mut out, app);
+
+    // leave the terminal as it was, even if drawing failed
+    execute!(out, cursor::Show, DisableMouseCapture, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
+
+fn event_loop(out: &mut impl Write, app: &mut App) -> io::Result<()> {
+    let mut redraw = true;
+    while !app.quit {
+        if redraw {
+            draw::draw(out, app)?;
+        }
+
+        // any event redraws the screen, e.g. after the terminal was resized
+        redraw = event::poll(POLL_INTERVAL)?;
+        if redraw {
+            let action = match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => key_action(key, app),
+                Event::Mouse(mouse) if mouse.kind == MouseEventKind::Down(MouseButton::Left) => {
+                    draw::slot_at(app, mouse.column, mouse.row).map(|(x, y)| Action::Click { x, y })
+                }
+                _ => None,
+            };
+
+            if let Some(action) = action {
+                app.handle(action);
+            }
+        }
+
+        redraw |= app.poll_hint();
+    }
+
+    Ok(())
+}
+
+// The keys of the window front-end
+fn key_action(key: KeyEvent, app: &App) -> Option<Action> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let action = match key.code {
+        KeyCode::Char('z') if ctrl => Action::Undo,
+        KeyCode::Char('y') if ctrl => Action::Redo,
+        // raw mode swallows the interrupt signal
+        KeyCode::Char('c') if ctrl => Action::Quit,
+        KeyCode::Char('u') => Action::Undo,
+        KeyCode::Char('r') if app.game.is_over() => Action::Restart,
+        KeyCode::Char('r') => Action::Redo,
+        KeyCode::Char('n') => Action::NewGame,
+        KeyCode::Char('h') => Action::Hint,
+        KeyCode::Up | KeyCode::Char('w') => Action::Move(Direction::Up),
+        KeyCode::Down | KeyCode::Char('s') => Action::Move(Direction::Down),
+        KeyCode::Left | KeyCode::Char('a') => Action::Move(Direction::Left),
+        KeyCode::Right | KeyCode::Char('d') => Action::Move(Direction::Right),
+        KeyCode::Char('q') => Action::Move(Direction::UpLeft),
+        KeyCode::Char('e') => Action::Move(Direction::UpRight),
+        KeyCode::Char('z') => Action::Move(Direction::DownLeft),
+        KeyCode::Char('c') => Action::Move(Direction::DownRight),
+        KeyCode::Enter => Action::Enter,
+        KeyCode::Esc => Action::Quit,
+        _ => return None,
+    };
+
+    Some(action)
+}